@@ -0,0 +1,185 @@
+//! Compact binary encoding for trees of [`Node<T>`].
+//!
+//! Each node is written in pre-order as its value followed by its number of children,
+//! every child then follows recursively. Child counts are written as little endian `u64`.
+//!
+//! With the `serde` feature enabled, values are encoded with `bincode` and only need to
+//! implement `Serialize` and `Deserialize`. Without it, values are encoded through the
+//! [`Encode`] trait, implemented here for primitives.
+use crate::{
+    error::NodeError,
+    node::{Node, NodeRef},
+};
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+#[cfg(not(feature = "std"))]
+use rclite::Rc;
+
+#[cfg(feature = "std")]
+use std::rc::Rc;
+
+/// Values that can be written to, and read back from, a byte buffer.
+pub trait Encode: Sized {
+    /// Append the encoded value to `buf`.
+    fn encode(&self, buf: &mut Vec<u8>);
+
+    /// Read a value from the front of `bytes`, advancing it past the consumed bytes.
+    ///
+    /// ### Return
+    /// - Result of the value, or [`NodeError::InvalidEncoding`] if `bytes` is too short or malformed.
+    fn decode(bytes: &mut &[u8]) -> Result<Self, NodeError>;
+}
+
+macro_rules! impl_encode {
+    ($($ty:ty),*) => {
+        $(
+            impl Encode for $ty {
+                #[inline]
+                fn encode(&self, buf: &mut Vec<u8>) {
+                    buf.extend_from_slice(&self.to_le_bytes());
+                }
+
+                #[inline]
+                fn decode(bytes: &mut &[u8]) -> Result<Self, NodeError> {
+                    const SIZE: usize = core::mem::size_of::<$ty>();
+                    let (head, tail) = bytes
+                        .split_first_chunk::<SIZE>()
+                        .ok_or(NodeError::InvalidEncoding)?;
+                    *bytes = tail;
+                    Ok(<$ty>::from_le_bytes(*head))
+                }
+            }
+        )*
+    };
+}
+
+impl_encode!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64);
+
+impl Encode for bool {
+    #[inline]
+    fn encode(&self, buf: &mut Vec<u8>) {
+        buf.push(*self as u8);
+    }
+
+    #[inline]
+    fn decode(bytes: &mut &[u8]) -> Result<Self, NodeError> {
+        match u8::decode(bytes)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(NodeError::InvalidEncoding),
+        }
+    }
+}
+
+#[cfg(not(feature = "serde"))]
+impl<T> Node<T>
+where
+    T: Encode,
+{
+    /// Encode the tree under `root` into a byte buffer.
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent(1u32);
+    /// let _ = Node::insert(&root, 2)?;
+    ///
+    /// let bytes = Node::encode(&root);
+    /// let copy = Node::<u32>::decode(&bytes)?;
+    /// assert_eq!(*copy.borrow().children()[0].borrow().value(), 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn encode(root: &NodeRef<T>) -> Vec<u8> {
+        let mut buf = Vec::new();
+        for node in Node::iter_dfs(Rc::clone(root)) {
+            let n = node.borrow();
+            n.value().encode(&mut buf);
+            (n.children().len() as u64).encode(&mut buf);
+        }
+        buf
+    }
+
+    /// Decode a tree previously written by [`Node::encode`], returning its root.
+    ///
+    /// ### Return
+    /// - Result of the new root, or [`NodeError::InvalidEncoding`] if `bytes` is truncated,
+    ///   malformed, or has trailing data.
+    pub fn decode(bytes: &[u8]) -> Result<NodeRef<T>, NodeError> {
+        let mut bytes = bytes;
+        let root = Node::from_pre_order(|| Node::decode_header(&mut bytes))?;
+        if bytes.is_empty() {
+            Ok(root)
+        } else {
+            Err(NodeError::InvalidEncoding)
+        }
+    }
+
+    fn decode_header(bytes: &mut &[u8]) -> Result<(T, usize), NodeError> {
+        let value = T::decode(bytes)?;
+        let count = usize::try_from(u64::decode(bytes)?).map_err(|_| NodeError::InvalidEncoding)?;
+        Ok((value, count))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T> Node<T>
+where
+    T: serde::Serialize + serde::de::DeserializeOwned,
+{
+    /// Encode the tree under `root` into a byte buffer.
+    ///
+    /// The signature matches the one without the `serde` feature, so enabling it does not
+    /// break existing callers.
+    ///
+    /// ### Panics
+    /// - If the `Serialize` impl of a value returns an error, which the impls of `serde`
+    ///   itself never do for values held in memory.
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent(String::from("a"));
+    /// let _ = Node::insert(&root, String::from("b"))?;
+    ///
+    /// let bytes = Node::encode(&root);
+    /// let copy = Node::<String>::decode(&bytes)?;
+    /// assert_eq!(*copy.borrow().children()[0].borrow().value(), "b");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn encode(root: &NodeRef<T>) -> Vec<u8> {
+        let mut buf = Vec::new();
+        for node in Node::iter_dfs(Rc::clone(root)) {
+            let n = node.borrow();
+            // writing into a `Vec` cannot fail, only a value refusing to serialize can
+            bincode::serialize_into(&mut buf, n.value()).expect("value failed to serialize");
+            (n.children().len() as u64).encode(&mut buf);
+        }
+        buf
+    }
+
+    /// Decode a tree previously written by [`Node::encode`], returning its root.
+    ///
+    /// ### Return
+    /// - Result of the new root, or [`NodeError::InvalidEncoding`] if `bytes` is truncated,
+    ///   malformed, or has trailing data.
+    pub fn decode(bytes: &[u8]) -> Result<NodeRef<T>, NodeError> {
+        let mut bytes = bytes;
+        let root = Node::from_pre_order(|| Node::decode_header(&mut bytes))?;
+        if bytes.is_empty() {
+            Ok(root)
+        } else {
+            Err(NodeError::InvalidEncoding)
+        }
+    }
+
+    fn decode_header(bytes: &mut &[u8]) -> Result<(T, usize), NodeError> {
+        let value = bincode::deserialize_from(&mut *bytes).map_err(|_| NodeError::InvalidEncoding)?;
+        let count = usize::try_from(u64::decode(bytes)?).map_err(|_| NodeError::InvalidEncoding)?;
+        Ok((value, count))
+    }
+}
@@ -2,10 +2,18 @@
 /// These errors are used to indicate issues with node manipulation, such as upgrading or downgrading a node,
 /// or when a node does not meet the expected type (e.g., leaf, parent, root).
 #[cfg(not(feature = "std"))]
-use core::{fmt, error::Error};
+use core::{
+    cell::{BorrowError, BorrowMutError},
+    fmt,
+    error::Error,
+};
 
 #[cfg(feature = "std")]
-use std::{fmt, error::Error};
+use std::{
+    cell::{BorrowError, BorrowMutError},
+    fmt,
+    error::Error,
+};
 
 #[derive(Debug, PartialEq, Eq)]
 #[non_exhaustive]
@@ -39,7 +47,31 @@ pub enum NodeError {
     ParentNodeNotFound,
 
     /// Raised when children are expected
-    ExpectedChildren
+    ExpectedChildren,
+
+    /// Raised when a node is expected to have exactly one child
+    NotSingleChild,
+
+    /// Raised when decoding bytes that are not a valid encoded tree
+    InvalidEncoding,
+
+    /// Raised when linking nodes would make a node its own ancestor
+    CycleDetected,
+
+    /// Raised when two trees expected to share the same shape differ
+    ShapeMismatch,
+
+    /// Raised when a child index is past the number of children
+    IndexOutOfBounds { index: usize, len: usize },
+
+    /// Raised when an operation would replace or remove the root of a tree
+    CannotReplaceRoot,
+
+    /// Raised when value and degree sequences do not describe a single tree
+    MalformedSequence,
+
+    /// Raised when an operation needs a root but the tree is empty
+    EmptyTree,
 }
 
 
@@ -55,9 +87,29 @@ impl fmt::Display for NodeError {
             Self::NotAParent => write!(f, "Expected a node Node::Parent"),
             Self::AlreadyBorrowed => write!(f, "Node is already borrowed mutably"),
             Self::ParentNodeNotFound => write!(f, "Parent not found"),
-            Self::ExpectedChildren => write!(f, "Expected the node to have children")
+            Self::ExpectedChildren => write!(f, "Expected the node to have children"),
+            Self::NotSingleChild => write!(f, "Expected the node to have exactly one child"),
+            Self::InvalidEncoding => write!(f, "Malformed binary encoding of a node tree"),
+            Self::CycleDetected => write!(f, "Node cannot become a descendant of itself"),
+            Self::ShapeMismatch => write!(f, "Trees do not share the same shape"),
+            Self::IndexOutOfBounds { index, len } => write!(f, "Child index {} is out of bounds for {} children", index, len),
+            Self::CannotReplaceRoot => write!(f, "Root node cannot be replaced or removed"),
+            Self::MalformedSequence => write!(f, "Sequences do not describe a single tree"),
+            Self::EmptyTree => write!(f, "Tree is empty and has no root"),
         }
     }
 }
 
-impl Error for NodeError {}
\ No newline at end of file
+impl Error for NodeError {}
+
+impl From<BorrowError> for NodeError {
+    fn from(_: BorrowError) -> Self {
+        Self::AlreadyBorrowed
+    }
+}
+
+impl From<BorrowMutError> for NodeError {
+    fn from(_: BorrowMutError) -> Self {
+        Self::AlreadyBorrowed
+    }
+}
\ No newline at end of file
@@ -25,14 +25,29 @@
 #[macro_use]
 extern crate alloc;
 
+pub mod encode;
 pub mod error;
 mod node;
+pub mod observer;
+mod tree;
 
-pub use crate::node::{Node, NodeRef, PrevNodeRef, NodeIter};
+pub use crate::node::{
+    DfsIter, EdgeIter, Node, NodeIter, NodeRef, PostOrderIter, PrevNodeRef, SiblingIndexIter,
+    SizeCache, TraversalOrder, TreeSnapshot,
+};
+pub use crate::tree::Tree;
+
+#[cfg(feature = "std")]
+pub use crate::node::WeakNodeRef;
 
 #[cfg(test)]
 mod tests {
-    use crate::{error::NodeError, node::NodeIter, Node, NodeRef};
+    use crate::{
+        error::NodeError, node::NodeIter, Node, NodeRef, SizeCache, TraversalOrder, Tree,
+    };
+
+    #[cfg(not(feature = "std"))]
+    use alloc::vec::Vec;
 
     fn assert_parent_eq<T>(parent: &NodeRef<T>, expected_parent: &NodeRef<T>) {
         assert!(NodeRef::ptr_eq(
@@ -41,6 +56,30 @@ mod tests {
         ));
     }
 
+    fn parent_of<T>(node: &NodeRef<T>) -> NodeRef<T> {
+        #[cfg(not(feature = "std"))]
+        return node.borrow().prev().unwrap();
+        #[cfg(feature = "std")]
+        return node.borrow().prev().unwrap().upgrade().unwrap();
+    }
+
+    /// Builds the tree used across the traversal tests.
+    /// ```text
+    ///     1
+    ///    / \
+    ///   2   3
+    ///      / \
+    ///     4   5
+    /// ```
+    fn sample_tree() -> Result<NodeRef<u8>, NodeError> {
+        let root: NodeRef<u8> = Node::parent(1);
+        let _ = Node::insert(&root, 2)?;
+        let child = Node::insert(&root, 3)?;
+        let _ = Node::insert(&child, 4)?;
+        let _ = Node::insert(&child, 5)?;
+        Ok(root)
+    }
+
     #[test]
     fn create_root_node() {
         let node: NodeRef<bool> = Node::parent(true);
@@ -166,6 +205,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::while_let_on_iterator)]
     fn iter_test() -> Result<(), NodeError> {
         let root: NodeRef<u8> = Node::parent(1);
         let _: NodeRef<u8> = Node::insert(&root, 2)?;
@@ -177,6 +217,7 @@ mod tests {
         let mut count = 1; 
         while let Some(node) = nodes.next() {
             // order printed out: 1, 2, 3, 4, 5
+            #[cfg(feature = "std")]
             println!("{}", node.borrow().value());
             assert!(node.borrow().value() == &count);
             count += 1;
@@ -184,4 +225,1791 @@ mod tests {
         Ok(())
         
     }
+
+    #[test]
+    fn trim_empty_parents() -> Result<(), NodeError> {
+        let root: NodeRef<u8> = Node::parent(1);
+        let empty: NodeRef<u8> = Node::parent(2);
+        let branch = Node::insert(&root, 3)?;
+        let nested_empty: NodeRef<u8> = Node::parent(4);
+        Node::insert_node(&root, &empty)?;
+        Node::insert_node(&branch, &nested_empty)?;
+
+        assert!(!empty.borrow().is_leaf());
+        assert_eq!(Node::trim_empty_parents(&root), 2);
+        assert!(empty.borrow().is_leaf());
+        assert!(nested_empty.borrow().is_leaf());
+        assert!(!branch.borrow().is_leaf());
+        assert!(root.borrow().is_root());
+        Ok(())
+    }
+
+    #[test]
+    fn reserve_children() -> Result<(), NodeError> {
+        let root: NodeRef<u8> = Node::parent(1);
+        Node::reserve(&root, 16)?;
+        let _ = Node::insert(&root, 2)?;
+        let children = root.borrow().children().as_ptr();
+        for value in 3..18 {
+            let _ = Node::insert(&root, value)?;
+        }
+        assert_eq!(root.borrow().children().as_ptr(), children);
+
+        let leaf = Node::leaf(1, None);
+        assert_eq!(Node::reserve(&leaf, 16), Err(NodeError::NotAParent));
+        Ok(())
+    }
+
+    #[test]
+    fn values_dfs() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        let left = root.borrow().children()[0].clone();
+        let _ = Node::insert(&left, 6)?;
+
+        let values: Vec<u8> = Node::values_dfs(root).collect();
+        assert_eq!(values, vec![1, 2, 6, 3, 4, 5]);
+        Ok(())
+    }
+
+    #[test]
+    fn promote_only_child() -> Result<(), NodeError> {
+        let root: NodeRef<u8> = Node::parent(1);
+        let first = Node::insert(&root, 2)?;
+        let middle = Node::insert(&root, 3)?;
+        let _ = Node::insert(&root, 4)?;
+        let grand_child = Node::insert(&middle, 5)?;
+        let _ = Node::insert(&grand_child, 6)?;
+
+        let promoted = Node::promote_only_child(&middle)?;
+        assert!(NodeRef::ptr_eq(&promoted, &grand_child));
+        assert!(NodeRef::ptr_eq(&root.borrow().children()[1], &grand_child));
+        assert_eq!(root.borrow().children().len(), 3);
+        assert_parent_eq(&parent_of(&grand_child), &root);
+        assert!(middle.borrow().prev().is_none());
+        assert!(!middle.borrow().has_children());
+
+        assert!(NodeRef::ptr_eq(&root.borrow().children()[0], &first));
+        Ok(())
+    }
+
+    #[test]
+    fn promote_only_child_with_siblings() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        let branch = root.borrow().children()[1].clone();
+        assert_eq!(
+            Node::promote_only_child(&branch).err(),
+            Some(NodeError::NotSingleChild)
+        );
+        assert_eq!(branch.borrow().children().len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn split_off_branch() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        let branch = root.borrow().children()[1].clone();
+
+        let tree = Node::split_off(&root, &branch)?;
+        assert!(NodeRef::ptr_eq(&tree, &branch));
+        assert!(tree.borrow().is_root());
+        assert_eq!(tree.borrow().children().len(), 2);
+        assert_eq!(root.borrow().children().len(), 1);
+
+        // a leaf comes back as a root as well
+        let leaf = root.borrow().children()[0].clone();
+        let tree = Node::split_off(&root, &leaf)?;
+        assert!(tree.borrow().is_root());
+        assert!(!tree.borrow().has_children());
+        assert_eq!(*tree.borrow().value(), 2);
+
+        let stranger = Node::leaf(9, None);
+        assert_eq!(
+            Node::split_off(&root, &stranger).err(),
+            Some(NodeError::ParentNodeNotFound)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn count_at_depth() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        assert_eq!(Node::count_at_depth(&root, 0), 1);
+        assert_eq!(Node::count_at_depth(&root, 1), 2);
+        assert_eq!(Node::count_at_depth(&root, 2), 2);
+        assert_eq!(Node::count_at_depth(&root, 3), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn path_to() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        let branch = root.borrow().children()[1].clone();
+        let leaf = branch.borrow().children()[1].clone();
+        assert_eq!(Node::path_to(&root, &root), Some(vec![]));
+        assert_eq!(Node::path_to(&root, &leaf), Some(vec![1, 1]));
+
+        let stranger = Node::leaf(5, None);
+        assert_eq!(Node::path_to(&root, &stranger), None);
+        Ok(())
+    }
+
+    #[test]
+    fn encode_round_trip() -> Result<(), NodeError> {
+        let root: NodeRef<u32> = Node::parent(1);
+        let _ = Node::insert(&root, 2)?;
+        let child = Node::insert(&root, 3)?;
+        let grand_child = Node::insert(&child, 40_000)?;
+        let _ = Node::insert(&grand_child, 5)?;
+        let _ = Node::insert(&child, 6)?;
+
+        let bytes = Node::encode(&root);
+        let decoded = Node::<u32>::decode(&bytes)?;
+        assert!(decoded.borrow().is_root());
+        assert_eq!(
+            Node::values_dfs(decoded.clone()).collect::<Vec<_>>(),
+            Node::values_dfs(root).collect::<Vec<_>>()
+        );
+        assert!(decoded.borrow().children()[0].borrow().is_leaf());
+        let decoded_child = decoded.borrow().children()[1].clone();
+        assert_parent_eq(&parent_of(&decoded_child), &decoded);
+
+        assert_eq!(
+            Node::<u32>::decode(&bytes[..bytes.len() - 1]).err(),
+            Some(NodeError::InvalidEncoding)
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn encode_serde_round_trip() -> Result<(), NodeError> {
+        let root: NodeRef<u32> = Node::parent(1);
+        let child = Node::insert(&root, 2)?;
+        let _ = Node::insert(&child, u32::MAX)?;
+        let decoded = Node::<u32>::decode(&Node::encode(&root))?;
+        assert!(Node::values_equal(&decoded, &[1, 2, u32::MAX]));
+
+        let root = Node::parent(String::from("root"));
+        let _ = Node::insert(&root, String::new())?;
+        let child = Node::insert(&root, String::from("branch"))?;
+        let _ = Node::insert(&child, String::from("leaf"))?;
+
+        let bytes = Node::encode(&root);
+        let decoded = Node::<String>::decode(&bytes)?;
+        assert!(decoded.borrow().is_root());
+        assert!(Node::values_equal(&decoded, &["root", "", "branch", "leaf"].map(String::from)));
+        let decoded_child = decoded.borrow().children()[1].clone();
+        assert_parent_eq(&parent_of(&decoded_child), &decoded);
+
+        assert_eq!(
+            Node::<String>::decode(&bytes[..bytes.len() - 1]).err(),
+            Some(NodeError::InvalidEncoding)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn is_isomorphic() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        let other: NodeRef<&str> = Node::parent("a");
+        let _ = Node::insert(&other, "b")?;
+        let branch = Node::insert(&other, "c")?;
+        let _ = Node::insert(&branch, "d")?;
+        let _ = Node::insert(&branch, "e")?;
+        assert!(Node::is_isomorphic(&root, &other));
+
+        let first = other.borrow().children()[0].clone();
+        let _ = Node::insert(&first, "f")?;
+        assert!(!Node::is_isomorphic(&root, &other));
+        Ok(())
+    }
+
+    #[test]
+    fn iter_with_sibling_index() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        let indexed: Vec<(usize, u8)> = Node::iter_with_sibling_index(root)
+            .map(|(index, node)| (index, *node.borrow().value()))
+            .collect();
+        assert_eq!(indexed, vec![(0, 1), (0, 2), (1, 3), (0, 4), (1, 5)]);
+        Ok(())
+    }
+
+    #[test]
+    fn drain_children_filter() -> Result<(), NodeError> {
+        let root: NodeRef<u8> = Node::parent(1);
+        for value in 2..8 {
+            let _ = Node::insert(&root, value)?;
+        }
+
+        let even = Node::drain_children_filter(&root, |c| c.borrow().value() % 2 == 0)?;
+        let drained: Vec<u8> = even.iter().map(|c| *c.borrow().value()).collect();
+        let remaining: Vec<u8> = root.borrow().children().iter().map(|c| *c.borrow().value()).collect();
+        assert_eq!(drained, vec![2, 4, 6]);
+        assert_eq!(remaining, vec![3, 5, 7]);
+        assert!(even.iter().all(|c| c.borrow().prev().is_none()));
+
+        let branch = root.borrow().children()[0].clone();
+        let _ = Node::insert(&branch, 8)?;
+        let _ = Node::drain_children_filter(&branch, |_| true)?;
+        assert!(branch.borrow().is_leaf());
+        Ok(())
+    }
+
+    #[test]
+    fn set_parent() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        let other: NodeRef<u8> = Node::parent(9);
+        let leaf = Node::leaf(6, None);
+
+        Node::set_parent(&leaf, Some(&other))?;
+        assert_parent_eq(&parent_of(&leaf), &other);
+        assert!(!other.borrow().has_children());
+
+        Node::set_parent(&leaf, None)?;
+        assert!(leaf.borrow().prev().is_none());
+
+        let branch = root.borrow().children()[1].clone();
+        let grand_child = branch.borrow().children()[0].clone();
+        assert_eq!(Node::set_parent(&root, Some(&grand_child)), Err(NodeError::CycleDetected));
+        assert_eq!(Node::set_parent(&branch, Some(&branch)), Err(NodeError::CycleDetected));
+        assert!(root.borrow().is_root());
+        Ok(())
+    }
+
+    #[test]
+    fn flatten() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        let values = |order| -> Vec<u8> {
+            Node::flatten(&root, order)
+                .iter()
+                .map(|node| *node.borrow().value())
+                .collect()
+        };
+        assert_eq!(values(TraversalOrder::Bfs), vec![1, 2, 3, 4, 5]);
+        assert_eq!(values(TraversalOrder::PreOrder), vec![1, 2, 3, 4, 5]);
+        assert_eq!(values(TraversalOrder::PostOrder), vec![2, 4, 5, 3, 1]);
+
+        let left = root.borrow().children()[0].clone();
+        let _ = Node::insert(&left, 6)?;
+        assert_eq!(values(TraversalOrder::Bfs), vec![1, 2, 3, 6, 4, 5]);
+        assert_eq!(values(TraversalOrder::PreOrder), vec![1, 2, 6, 3, 4, 5]);
+        assert_eq!(values(TraversalOrder::PostOrder), vec![6, 2, 4, 5, 3, 1]);
+        Ok(())
+    }
+
+    #[test]
+    fn degree() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        let branch = root.borrow().children()[1].clone();
+        let _ = Node::insert(&branch, 6)?;
+        assert_eq!(root.borrow().degree(), 2);
+        assert_eq!(branch.borrow().degree(), 3);
+        assert_eq!(branch.borrow().children()[0].borrow().degree(), 0);
+        assert_eq!(Node::max_degree(&root), 3);
+        assert_eq!(Node::max_degree(&Node::leaf(1, None)), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn get_by_value_path() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        let found = Node::get_by_value_path(&root, &[3, 5]).unwrap();
+        assert_eq!(*found.borrow().value(), 5);
+        assert!(NodeRef::ptr_eq(&Node::get_by_value_path(&root, &[]).unwrap(), &root));
+        assert!(Node::get_by_value_path(&root, &[2, 4]).is_none());
+        assert!(Node::get_by_value_path(&root, &[3, 6]).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn update_with_context() -> Result<(), NodeError> {
+        let root: NodeRef<usize> = Node::parent(0);
+        let _ = Node::insert(&root, 0)?;
+        let branch = Node::insert(&root, 0)?;
+        let _ = Node::insert(&branch, 0)?;
+        let leaf = Node::insert(&branch, 0)?;
+
+        Node::update_with_context(&root, |value, depth, index| *value = depth * 100 + index);
+        assert_eq!(*root.borrow().value(), 0);
+        assert_eq!(*branch.borrow().value(), 101);
+        assert_eq!(*leaf.borrow().value(), 201);
+        assert_eq!(*root.borrow().children()[0].borrow().value(), 100);
+        Ok(())
+    }
+
+    #[test]
+    fn prepend() -> Result<(), NodeError> {
+        let root: NodeRef<u8> = Node::parent(1);
+        let leaf = Node::insert(&root, 2)?;
+        let _ = Node::prepend(&root, 3)?;
+        let front = Node::prepend(&root, 4)?;
+        let values: Vec<u8> = root.borrow().children().iter().map(|c| *c.borrow().value()).collect();
+        assert_eq!(values, vec![4, 3, 2]);
+        assert_parent_eq(&parent_of(&front), &root);
+
+        let only = Node::prepend(&leaf, 5)?;
+        assert!(!leaf.borrow().is_leaf());
+        assert!(NodeRef::ptr_eq(&leaf.borrow().children()[0], &only));
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn observer_fires_on_insert_and_pop() -> Result<(), NodeError> {
+        use crate::observer::TreeObserver;
+        use std::{cell::RefCell, rc::Rc};
+
+        #[derive(Default)]
+        struct Recorder(RefCell<Vec<(&'static str, u8, u8)>>);
+
+        impl TreeObserver<u8> for Recorder {
+            fn on_insert(&self, parent: &NodeRef<u8>, child: &NodeRef<u8>) {
+                let event = ("insert", *parent.borrow().value(), *child.borrow().value());
+                self.0.borrow_mut().push(event);
+            }
+
+            fn on_remove(&self, parent: &NodeRef<u8>, child: &NodeRef<u8>) {
+                let event = ("remove", *parent.borrow().value(), *child.borrow().value());
+                self.0.borrow_mut().push(event);
+            }
+        }
+
+        let root: NodeRef<u8> = Node::parent(1);
+        let _ = Node::insert(&root, 2)?;
+        let recorder = Rc::new(Recorder::default());
+        Node::set_observer(&root, recorder.clone())?;
+
+        let child = Node::insert(&root, 3)?;
+        let grand_child = Node::leaf(4, None);
+        Node::insert_node(&child, &grand_child)?;
+        assert!(Node::pop(&child, &grand_child)?);
+        assert_eq!(
+            *recorder.0.borrow(),
+            vec![("insert", 1, 3), ("insert", 3, 4), ("remove", 3, 4)]
+        );
+
+        assert!(Node::clear_observer(&root).is_some());
+        let _ = Node::insert(&root, 5)?;
+        assert_eq!(recorder.0.borrow().len(), 3);
+        assert_eq!(
+            Node::set_observer(&child, recorder.clone()),
+            Err(NodeError::ExpectedARootNode)
+        );
+
+        // every structural change is reported, not only insert and pop
+        Node::set_observer(&root, recorder.clone())?;
+        recorder.0.borrow_mut().clear();
+        let front = Node::prepend(&root, 6)?;
+        let _ = Node::split_off(&root, &front)?;
+        Node::splice_out(&child)?;
+        assert_eq!(
+            *recorder.0.borrow(),
+            vec![("insert", 1, 6), ("remove", 1, 6), ("remove", 1, 3)]
+        );
+
+        // clones do not carry the observer, and a root linked beneath another node loses it
+        let copy = Node::clone_shallow(&root);
+        assert!(Node::clear_observer(&copy).is_none());
+        let other: NodeRef<u8> = Node::parent(7);
+        Node::set_observer(&other, recorder.clone())?;
+        Node::insert_node(&root, &other)?;
+        let _ = Node::split_off(&root, &other)?;
+        assert!(Node::clear_observer(&other).is_none());
+
+        // rerooting hands the observer over to the new root
+        recorder.0.borrow_mut().clear();
+        let first = root.borrow().children()[0].clone();
+        let new_root = Node::reroot(&first)?;
+        assert!(Node::clear_observer(&root).is_none());
+        let _ = Node::insert(&new_root, 8)?;
+        assert_eq!(*recorder.0.borrow(), vec![("insert", 2, 8)]);
+
+        // the observer of a dropped root is released once another observer is registered,
+        // and never hears about a tree of another type taking the place of that root
+        #[derive(Default)]
+        struct Counter(std::cell::Cell<usize>);
+
+        impl TreeObserver<i8> for Counter {
+            fn on_insert(&self, _parent: &NodeRef<i8>, _child: &NodeRef<i8>) {
+                self.0.set(self.0.get() + 1);
+            }
+        }
+
+        let temporary: NodeRef<u8> = Node::parent(9);
+        Node::set_observer(&temporary, recorder.clone())?;
+        let registered = Rc::strong_count(&recorder);
+        recorder.0.borrow_mut().clear();
+        drop(temporary);
+        let _ = Node::insert(&new_root, 10)?;
+        assert_eq!(Rc::strong_count(&recorder), registered);
+
+        let before: Vec<NodeRef<i8>> = (0..8).map(Node::parent).collect();
+        let counter = Rc::new(Counter::default());
+        Node::set_observer(&before[0], counter.clone())?;
+        assert_eq!(Rc::strong_count(&recorder), registered - 1);
+        let after: Vec<NodeRef<i8>> = (0..8).map(Node::parent).collect();
+        for root in before.iter().chain(&after) {
+            let _ = Node::insert(root, 1)?;
+        }
+        assert_eq!(counter.0.get(), 1);
+        assert_eq!(*recorder.0.borrow(), vec![("insert", 2, 10)]);
+        Ok(())
+    }
+
+    #[test]
+    fn snapshot_restore() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        let snapshot = Node::snapshot(&root);
+        assert_eq!(snapshot.len(), 5);
+
+        let branch = root.borrow().children()[1].clone();
+        let leaf = branch.borrow().children()[0].clone();
+        assert!(Node::pop(&branch, &leaf)?);
+        let _ = Node::insert(&root, 6)?;
+
+        let restored = Node::restore(&snapshot);
+        assert!(restored.borrow().is_root());
+        assert!(Node::is_isomorphic(&restored, &sample_tree()?));
+        assert_eq!(Node::values_dfs(restored).collect::<Vec<_>>(), vec![1, 2, 3, 4, 5]);
+
+        // a parent without children is restored as a parent, not a leaf
+        let empty: NodeRef<u8> = Node::parent(7);
+        Node::insert_node(&root, &empty)?;
+        let restored = Node::restore(&Node::snapshot(&root));
+        assert!(!restored.borrow().children()[3].borrow().is_leaf());
+        assert!(restored.borrow().children()[2].borrow().is_leaf());
+        Ok(())
+    }
+
+    #[test]
+    fn shared_prefix_len() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        let left = root.borrow().children()[0].clone();
+        let branch = root.borrow().children()[1].clone();
+        let first = branch.borrow().children()[0].clone();
+        let second = branch.borrow().children()[1].clone();
+        let nested = Node::insert(&first, 6)?;
+
+        assert_eq!(Node::shared_prefix_len(&first, &second), 1);
+        assert_eq!(Node::shared_prefix_len(&nested, &second), 1);
+        assert_eq!(Node::shared_prefix_len(&nested, &first), 2);
+        assert_eq!(Node::shared_prefix_len(&left, &nested), 0);
+
+        let stranger = sample_tree()?;
+        assert_eq!(Node::shared_prefix_len(&stranger, &root), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn is_full_and_perfect() -> Result<(), NodeError> {
+        let perfect: NodeRef<u8> = Node::parent(1);
+        for value in [2, 3] {
+            let child = Node::insert(&perfect, value)?;
+            let _ = Node::insert(&child, value * 10)?;
+            let _ = Node::insert(&child, value * 10 + 1)?;
+        }
+        assert!(Node::is_full(&perfect, 2));
+        assert!(Node::is_perfect(&perfect, 2));
+        assert!(!Node::is_full(&perfect, 3));
+
+        // full but the leaves sit at depths 1 and 2
+        let irregular = sample_tree()?;
+        assert!(Node::is_full(&irregular, 2));
+        assert!(!Node::is_perfect(&irregular, 2));
+
+        let branch = irregular.borrow().children()[1].clone();
+        let _ = Node::insert(&branch, 6)?;
+        assert!(!Node::is_full(&irregular, 2));
+        assert!(!Node::is_perfect(&irregular, 2));
+        Ok(())
+    }
+
+    #[test]
+    fn nodes_by_depth() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        let levels = Node::nodes_by_depth(&root);
+        assert_eq!(levels.keys().copied().collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert_eq!(levels[&0].len(), 1);
+        assert_eq!(levels[&1].len(), 2);
+        assert_eq!(levels[&2].len(), 2);
+        assert_eq!(*levels[&2][1].borrow().value(), 5);
+        Ok(())
+    }
+
+    #[test]
+    fn truncate_depth() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        let branch = root.borrow().children()[1].clone();
+        let leaf = branch.borrow().children()[0].clone();
+        let _ = Node::insert(&leaf, 6)?;
+
+        assert_eq!(Node::truncate_depth(&root, 1), 3);
+        assert_eq!(Node::values_dfs(root.clone()).collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert!(branch.borrow().is_leaf());
+        assert!(leaf.borrow().prev().is_none());
+
+        assert_eq!(Node::truncate_depth(&root, 0), 2);
+        assert!(root.borrow().is_root());
+        assert!(!root.borrow().has_children());
+        Ok(())
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn weak_node_ref() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        let branch = root.borrow().children()[1].clone();
+        let cached = Node::downgrade_ref(&branch);
+        assert!(NodeRef::ptr_eq(&Node::upgrade_ref(&cached).unwrap(), &branch));
+
+        assert!(Node::pop(&root, &branch)?);
+        drop(branch);
+        assert!(Node::upgrade_ref(&cached).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn retain_paths_to() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        let left = root.borrow().children()[0].clone();
+        let branch = root.borrow().children()[1].clone();
+        let deep = branch.borrow().children()[1].clone();
+        let _ = Node::insert(&deep, 10)?;
+        let _ = Node::insert(&deep, 11)?;
+
+        assert!(Node::retain_paths_to(&root, |value| *value >= 10 && value % 2 == 0));
+        assert_eq!(Node::values_dfs(root.clone()).collect::<Vec<_>>(), vec![1, 3, 5, 10]);
+        assert!(left.borrow().prev().is_none());
+        assert_eq!(branch.borrow().degree(), 1);
+
+        assert!(!Node::retain_paths_to(&root, |value| *value == 42));
+        assert!(!root.borrow().has_children());
+        Ok(())
+    }
+
+    #[cfg(feature = "petgraph")]
+    #[test]
+    fn to_petgraph() -> Result<(), NodeError> {
+        use petgraph::graph::NodeIndex;
+
+        let root = sample_tree()?;
+        let graph = Node::to_petgraph(&root);
+        assert_eq!(graph.node_count(), 5);
+        assert_eq!(graph.edge_count(), 4);
+        assert_eq!(graph[NodeIndex::new(0)], 1);
+        let mut children: Vec<u8> = graph.neighbors(NodeIndex::new(2)).map(|index| graph[index]).collect();
+        children.sort();
+        assert_eq!(children, vec![4, 5]);
+        Ok(())
+    }
+
+    #[test]
+    fn insert_sorted() -> Result<(), NodeError> {
+        let root: NodeRef<u8> = Node::parent(0);
+        let leaf = Node::insert(&root, 10)?;
+        for value in [5, 1, 9, 3, 7, 3] {
+            let child = Node::insert_sorted(&leaf, value)?;
+            assert_parent_eq(&parent_of(&child), &leaf);
+        }
+        let values: Vec<u8> = leaf.borrow().children().iter().map(|c| *c.borrow().value()).collect();
+        assert_eq!(values, vec![1, 3, 3, 5, 7, 9]);
+        Ok(())
+    }
+
+    #[test]
+    fn mirror() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        let left = root.borrow().children()[0].clone();
+        let _ = Node::insert(&left, 6)?;
+        let _ = Node::insert(&left, 7)?;
+
+        let mirrored = Node::mirror(&root);
+        assert!(mirrored.borrow().is_root());
+        assert_eq!(Node::values_dfs(mirrored.clone()).collect::<Vec<_>>(), vec![1, 3, 5, 4, 2, 7, 6]);
+        assert_eq!(Node::values_dfs(root.clone()).collect::<Vec<_>>(), vec![1, 2, 6, 7, 3, 4, 5]);
+
+        let mirrored_left = mirrored.borrow().children()[1].clone();
+        assert_parent_eq(&parent_of(&mirrored_left), &mirrored);
+        assert!(!NodeRef::ptr_eq(&mirrored_left, &left));
+        Ok(())
+    }
+
+    #[test]
+    fn min_max_value() -> Result<(), NodeError> {
+        let root: NodeRef<i32> = Node::parent(5);
+        let _ = Node::insert(&root, 7)?;
+        let branch = Node::insert(&root, 3)?;
+        let lowest = Node::insert(&branch, -2)?;
+        let highest = Node::insert(&branch, 11)?;
+        let _ = Node::insert(&highest, 11)?;
+
+        assert!(NodeRef::ptr_eq(&Node::min_value(&root).unwrap(), &lowest));
+        assert!(NodeRef::ptr_eq(&Node::max_value(&root).unwrap(), &highest));
+        Ok(())
+    }
+
+    #[test]
+    fn ancestor_at_depth() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        let branch = root.borrow().children()[1].clone();
+        let leaf = branch.borrow().children()[0].clone();
+        let deep = Node::insert(&leaf, 6)?;
+
+        assert!(NodeRef::ptr_eq(&Node::ancestor_at_depth(&deep, 0).unwrap(), &root));
+        assert!(NodeRef::ptr_eq(&Node::ancestor_at_depth(&deep, 1).unwrap(), &branch));
+        assert!(NodeRef::ptr_eq(&Node::ancestor_at_depth(&deep, 3).unwrap(), &deep));
+        assert!(Node::ancestor_at_depth(&deep, 4).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn edges() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        let edges: Vec<(u8, u8)> = Node::edges(root.clone())
+            .map(|(parent, child)| (*parent.borrow().value(), *child.borrow().value()))
+            .collect();
+        assert_eq!(edges, vec![(1, 2), (1, 3), (3, 4), (3, 5)]);
+        assert_eq!(edges.len(), Node::iter(root).count() - 1);
+        assert_eq!(Node::edges(Node::leaf(1, None)).count(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn set_root_value() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        assert_eq!(Node::set_root_value(&root, 10), Ok(1));
+        assert_eq!(*root.borrow().value(), 10);
+
+        let child = root.borrow().children()[0].clone();
+        assert_eq!(Node::set_root_value(&child, 20), Err(NodeError::ExpectedARootNode));
+        assert_eq!(*child.borrow().value(), 2);
+
+        let _held = root.borrow();
+        assert_eq!(Node::set_root_value(&root, 30), Err(NodeError::AlreadyBorrowed));
+        Ok(())
+    }
+
+    #[test]
+    fn distinct_value_count() -> Result<(), NodeError> {
+        let root: NodeRef<u8> = Node::parent(1);
+        let child = Node::insert(&root, 2)?;
+        let _ = Node::insert(&root, 2)?;
+        let _ = Node::insert(&child, 1)?;
+        let _ = Node::insert(&child, 3)?;
+        assert_eq!(Node::distinct_value_count(&root), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn zip_for_each() -> Result<(), NodeError> {
+        let a = sample_tree()?;
+        let b: NodeRef<u32> = Node::parent(10);
+        let _ = Node::insert(&b, 20)?;
+        let branch = Node::insert(&b, 30)?;
+        let _ = Node::insert(&branch, 40)?;
+        let _ = Node::insert(&branch, 50)?;
+
+        let mut pairs = vec![];
+        Node::zip_for_each(&a, &b, |a, b| pairs.push((*a, *b)))?;
+        assert_eq!(pairs, vec![(1, 10), (2, 20), (3, 30), (4, 40), (5, 50)]);
+        Ok(())
+    }
+
+    #[test]
+    fn zip_for_each_shape_mismatch() -> Result<(), NodeError> {
+        let a = sample_tree()?;
+        let b = sample_tree()?;
+        let left = b.borrow().children()[0].clone();
+        let _ = Node::insert(&left, 6)?;
+
+        let mut visited = 0;
+        assert_eq!(
+            Node::zip_for_each(&a, &b, |_, _| visited += 1),
+            Err(NodeError::ShapeMismatch)
+        );
+        assert_eq!(visited, 1);
+        Ok(())
+    }
+
+    #[cfg(not(feature = "std"))]
+    #[test]
+    fn no_std_iter() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        let left = root.borrow().children()[0].clone();
+        let _ = Node::insert(&left, 6)?;
+
+        let values: Vec<u8> = Node::iter(root).map(|node| *node.borrow().value()).collect();
+        assert_eq!(values, vec![1, 2, 3, 6, 4, 5]);
+        Ok(())
+    }
+
+    #[test]
+    fn size_cache() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        let cache = SizeCache::new(&root);
+        assert_eq!(cache.len(), 5);
+
+        for node in Node::iter(root.clone()) {
+            let fresh = Node::iter(node.clone()).count();
+            assert_eq!(cache.size_of(&node), Some(fresh));
+        }
+        assert_eq!(cache.size_of(&Node::parent(0)), None);
+
+        // removed nodes stay alive with the cache, so new nodes never take their address
+        let branch = root.borrow().children()[1].clone();
+        let _ = Node::split_off(&root, &branch)?;
+        drop(branch);
+        for value in 0..8 {
+            assert_eq!(cache.size_of(&Node::parent(value)), None);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn partition_children() -> Result<(), NodeError> {
+        let root = Node::parent(0);
+        for value in 1..=4 {
+            let _ = Node::insert(&root, value)?;
+        }
+
+        let (left, right) = Node::partition_children(&root, 2)?;
+        let values = |nodes: &[NodeRef<u8>]| {
+            nodes.iter().map(|n| *n.borrow().value()).collect::<Vec<_>>()
+        };
+        assert_eq!(values(&left), vec![1, 2]);
+        assert_eq!(values(&right), vec![3, 4]);
+        assert_eq!(root.borrow().children().len(), 4);
+
+        assert_eq!(
+            Node::partition_children(&root, 5).err(),
+            Some(NodeError::IndexOutOfBounds { index: 5, len: 4 })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn iter_to_depth() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        let values: Vec<u8> = Node::iter_to_depth(root.clone(), 1)
+            .map(|node| *node.borrow().value())
+            .collect();
+        assert_eq!(values, vec![1, 2, 3]);
+        assert_eq!(Node::iter_to_depth(root, 0).count(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn reorder_child() -> Result<(), NodeError> {
+        let root = Node::parent(0);
+        for value in 1..=4 {
+            let _ = Node::insert(&root, value)?;
+        }
+        let values = |root: &NodeRef<u8>| {
+            root.borrow().children().iter().map(|n| *n.borrow().value()).collect::<Vec<_>>()
+        };
+
+        Node::reorder_child(&root, 0, 2)?;
+        assert_eq!(values(&root), vec![2, 3, 1, 4]);
+
+        Node::reorder_child(&root, 3, 0)?;
+        assert_eq!(values(&root), vec![4, 2, 3, 1]);
+        assert_parent_eq(&parent_of(&root.borrow().children()[0]), &root);
+
+        assert_eq!(
+            Node::reorder_child(&root, 1, 4).err(),
+            Some(NodeError::IndexOutOfBounds { index: 4, len: 4 })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn leaf_values() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        assert_eq!(Node::leaf_values(&root), vec![2, 4, 5]);
+        Ok(())
+    }
+
+    #[test]
+    fn upgrade_with_children() -> Result<(), NodeError> {
+        let root = Node::parent(0);
+        let leaf = Node::insert(&root, 1)?;
+        let children: Vec<_> = (2..=4).map(|value| Node::leaf(value, None)).collect();
+
+        Node::upgrade_with_children(&leaf, children)?;
+        assert!(!leaf.borrow().is_leaf());
+        let values: Vec<u8> = leaf.borrow().children().iter().map(|n| *n.borrow().value()).collect();
+        assert_eq!(values, vec![2, 3, 4]);
+        for child in leaf.borrow().children() {
+            assert_parent_eq(&parent_of(child), &leaf);
+        }
+
+        assert_eq!(
+            Node::upgrade_with_children(&leaf, vec![Node::leaf(5, None)]).err(),
+            Some(NodeError::ParentUpgradeNotAllowed)
+        );
+
+        // a child linked elsewhere is moved over, and an ancestor cannot become a child
+        let root = sample_tree()?;
+        let two = root.borrow().children()[0].clone();
+        let three = root.borrow().children()[1].clone();
+        let four = three.borrow().children()[0].clone();
+        Node::upgrade_with_children(&two, vec![four.clone()])?;
+        assert_parent_eq(&parent_of(&four), &two);
+        assert_eq!(three.borrow().degree(), 1);
+        assert!(Node::values_equal(&root, &[1, 2, 3, 4, 5]));
+
+        let five = three.borrow().children()[0].clone();
+        assert_eq!(
+            Node::upgrade_with_children(&five, vec![three.clone()]).err(),
+            Some(NodeError::CycleDetected)
+        );
+        assert!(five.borrow().is_leaf());
+        Ok(())
+    }
+
+    #[test]
+    fn read_value() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        let child = root.borrow().children()[1].clone();
+        assert_eq!(Node::read_value(&child), 3);
+
+        // the borrow is released, so the node can be mutated right after
+        *child.borrow_mut().value_mut() = 7;
+        assert_eq!(Node::read_value(&child), 7);
+        Ok(())
+    }
+
+    #[test]
+    fn children_snapshot() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        assert_eq!(Node::children_snapshot(&root)?.len(), 2);
+        let leaf = Node::children_snapshot(&root)?[0].clone();
+        assert!(Node::children_snapshot(&leaf)?.is_empty());
+
+        let guard = root.borrow_mut();
+        assert_eq!(Node::children_snapshot(&root).err(), Some(NodeError::AlreadyBorrowed));
+        drop(guard);
+        Ok(())
+    }
+
+    #[test]
+    fn fold_up() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        let sum = |value: &u8, children: &[u32]| *value as u32 + children.iter().sum::<u32>();
+        assert_eq!(Node::fold_up(&root, sum), 15);
+
+        let right = root.borrow().children()[1].clone();
+        assert_eq!(Node::fold_up(&right, sum), 12);
+        Ok(())
+    }
+
+    #[test]
+    fn index_out_of_bounds() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        let err = Node::partition_children(&root, 7).unwrap_err();
+        assert_eq!(err, NodeError::IndexOutOfBounds { index: 7, len: 2 });
+
+        let message = format!("{}", err);
+        assert!(message.contains('7') && message.contains('2'));
+
+        let err = Node::insert_at(&root, 3, 6).unwrap_err();
+        assert_eq!(err, NodeError::IndexOutOfBounds { index: 3, len: 2 });
+        assert_eq!(root.borrow().degree(), 2);
+
+        let child = Node::insert_at(&root, 2, 6)?;
+        assert!(NodeRef::ptr_eq(&root.borrow().children()[2], &child));
+        Ok(())
+    }
+
+    #[test]
+    fn values_equal() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        assert!(Node::values_equal(&root, &[1, 2, 3, 4, 5]));
+        assert!(!Node::values_equal(&root, &[1, 2, 3, 4]));
+        assert!(!Node::values_equal(&root, &[1, 2, 3, 4, 5, 6]));
+        Ok(())
+    }
+
+    #[test]
+    fn siblings() -> Result<(), NodeError> {
+        let root = Node::parent(0);
+        let first = Node::insert(&root, 1)?;
+        let middle = Node::insert(&root, 2)?;
+        let _ = Node::insert(&root, 3)?;
+        let only = Node::insert(&first, 4)?;
+
+        let values: Vec<u8> = Node::siblings(&middle).iter().map(|n| *n.borrow().value()).collect();
+        assert_eq!(values, vec![1, 3]);
+        assert!(Node::siblings(&only).is_empty());
+        assert!(Node::siblings(&root).is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn to_indented() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        assert_eq!(Node::to_indented(&root, "  "), "1\n  2\n  3\n    4\n    5\n");
+        Ok(())
+    }
+
+    #[test]
+    fn graft_many() -> Result<(), NodeError> {
+        let root = Node::leaf(0, None);
+        let subtrees = vec![sample_tree()?, Node::parent(6), Node::leaf(7, None)];
+        Node::graft_many(&root, subtrees)?;
+
+        let values: Vec<u8> = root.borrow().children().iter().map(|n| *n.borrow().value()).collect();
+        assert_eq!(values, vec![1, 6, 7]);
+        for child in root.borrow().children() {
+            assert_parent_eq(&parent_of(child), &root);
+        }
+        assert_eq!(Node::iter(root.clone()).count(), 8);
+
+        let child = root.borrow().children()[0].clone();
+        assert_eq!(Node::graft_many(&child, [root.clone()]).err(), Some(NodeError::CycleDetected));
+
+        // a subtree linked elsewhere is moved over, and only once when listed twice
+        let other = sample_tree()?;
+        let branch = other.borrow().children()[1].clone();
+        let target = Node::parent(9);
+        Node::graft_many(&target, [branch.clone(), branch.clone()])?;
+        assert_eq!(target.borrow().degree(), 1);
+        assert_parent_eq(&parent_of(&branch), &target);
+        assert!(Node::values_equal(&other, &[1, 2]));
+        Ok(())
+    }
+
+    #[test]
+    fn modify_at_path() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        Node::modify_at_path(&root, &[1, 0], |value| *value = 40)?;
+        assert!(Node::values_equal(&root, &[1, 2, 3, 40, 5]));
+
+        Node::modify_at_path(&root, &[], |value| *value += 1)?;
+        assert_eq!(*root.borrow().value(), 2);
+
+        assert_eq!(
+            Node::modify_at_path(&root, &[1, 2], |value| *value = 0).err(),
+            Some(NodeError::IndexOutOfBounds { index: 2, len: 2 })
+        );
+        assert_eq!(
+            Node::modify_at_path(&root, &[0, 0], |value| *value = 0).err(),
+            Some(NodeError::IndexOutOfBounds { index: 0, len: 0 })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn child_values() -> Result<(), NodeError> {
+        let root = Node::parent(0);
+        for value in [3, 1, 2] {
+            let _ = Node::insert(&root, value)?;
+        }
+        assert_eq!(Node::child_values(&root), vec![3, 1, 2]);
+
+        let leaf = root.borrow().children()[0].clone();
+        assert!(Node::child_values(&leaf).is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn dedup_children() -> Result<(), NodeError> {
+        let root = Node::parent(0);
+        let mut children = Vec::new();
+        for value in [1, 2, 1, 3, 2, 1] {
+            children.push(Node::insert(&root, value)?);
+        }
+        let _ = Node::insert(&children[2], 9)?;
+
+        assert_eq!(Node::dedup_children(&root)?, 3);
+        assert_eq!(Node::child_values(&root), vec![1, 2, 3]);
+        assert!(children[2].borrow().is_root());
+        assert_eq!(Node::dedup_children(&root)?, 0);
+
+        let leaf = root.borrow().children()[0].clone();
+        assert_eq!(Node::dedup_children(&leaf).err(), Some(NodeError::NotAParent));
+        Ok(())
+    }
+
+    #[test]
+    fn values_post_order() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        let values: Vec<u8> = Node::values_post_order(root).collect();
+        assert_eq!(values, vec![2, 4, 5, 3, 1]);
+        Ok(())
+    }
+
+    #[test]
+    fn clone_shallow_and_deep() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        let shallow = Node::clone_shallow(&root);
+        let deep = Node::deep_clone(&root);
+        assert!(!NodeRef::ptr_eq(&shallow, &root));
+
+        for (original, shared) in root.borrow().children().iter().zip(shallow.borrow().children()) {
+            assert!(NodeRef::ptr_eq(original, shared));
+        }
+        for (original, copy) in root.borrow().children().iter().zip(deep.borrow().children()) {
+            assert!(!NodeRef::ptr_eq(original, copy));
+        }
+
+        // mutations through the shared children show up in the shallow clone only
+        *root.borrow().children()[0].borrow_mut().value_mut() = 20;
+        assert!(Node::values_equal(&shallow, &[1, 20, 3, 4, 5]));
+        assert!(Node::values_equal(&deep, &[1, 2, 3, 4, 5]));
+        Ok(())
+    }
+
+    #[test]
+    fn reroot() -> Result<(), NodeError> {
+        let old_root = sample_tree()?;
+        let right = old_root.borrow().children()[1].clone();
+        let leaf = right.borrow().children()[0].clone();
+
+        let root = Node::reroot(&leaf)?;
+        assert!(NodeRef::ptr_eq(&root, &leaf));
+        assert!(root.borrow().is_root());
+        assert!(!old_root.borrow().is_root());
+        assert_parent_eq(&parent_of(&right), &leaf);
+        assert_parent_eq(&parent_of(&old_root), &right);
+
+        // 4 -> 3 -> (5, 1 -> 2)
+        assert!(Node::values_equal(&root, &[4, 3, 5, 1, 2]));
+        assert_eq!(Node::path_to(&root, &old_root), Some(vec![0, 1]));
+        Ok(())
+    }
+
+    #[test]
+    fn count_paths_summing_to() -> Result<(), NodeError> {
+        // paths: 1-2, 1-3-4, 1-3-5
+        let root = sample_tree()?;
+        assert_eq!(Node::count_paths_summing_to(&root, 3), 1);
+        assert_eq!(Node::count_paths_summing_to(&root, 8), 1);
+        assert_eq!(Node::count_paths_summing_to(&root, 4), 0);
+
+        let right = root.borrow().children()[1].clone();
+        let _ = Node::insert(&right, 4)?;
+        assert_eq!(Node::count_paths_summing_to(&root, 8), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn iter_map() -> Result<(), NodeError> {
+        let root = Node::parent("root");
+        let _ = Node::insert(&root, "a")?;
+        let child = Node::insert(&root, "abc")?;
+        let _ = Node::insert(&child, "ab")?;
+
+        let lengths: Vec<usize> = Node::iter_map(root, |value| value.len()).collect();
+        assert_eq!(lengths, vec![4, 1, 3, 2]);
+        Ok(())
+    }
+
+    #[test]
+    fn keep_matching_branches() -> Result<(), NodeError> {
+        let branches = sample_tree()?;
+        let paths = sample_tree()?;
+
+        assert!(Node::keep_matching_branches(&branches, |value| *value == 3));
+        assert!(Node::retain_paths_to(&paths, |value| *value == 3));
+        assert!(Node::values_equal(&branches, &[1, 3, 4, 5]));
+        assert!(Node::values_equal(&paths, &[1, 3]));
+
+        assert!(!Node::keep_matching_branches(&branches, |value| *value == 9));
+        assert!(!branches.borrow().has_children());
+        Ok(())
+    }
+
+    #[test]
+    fn borrow_error_conversion() {
+        let root = Node::parent(1);
+        let guard = root.borrow();
+        let err: NodeError = root.try_borrow_mut().unwrap_err().into();
+        assert_eq!(err, NodeError::AlreadyBorrowed);
+        drop(guard);
+
+        let guard = root.borrow_mut();
+        let err: NodeError = root.try_borrow().unwrap_err().into();
+        assert_eq!(err, NodeError::AlreadyBorrowed);
+        drop(guard);
+    }
+
+    #[test]
+    fn estimate_bytes() -> Result<(), NodeError> {
+        let root = Node::parent(0u8);
+        let mut previous = Node::estimate_bytes(&root);
+        for value in 1..=4 {
+            let _ = Node::insert(&root, value)?;
+            let estimate = Node::estimate_bytes(&root);
+            assert!(estimate > previous);
+            previous = estimate;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn nearest_ancestor() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        let right = root.borrow().children()[1].clone();
+        let leaf = right.borrow().children()[0].clone();
+
+        let found = Node::nearest_ancestor(&leaf, |value| *value % 2 == 1).unwrap();
+        assert!(NodeRef::ptr_eq(&found, &right));
+        let found = Node::nearest_ancestor(&leaf, |value| *value < 2).unwrap();
+        assert!(NodeRef::ptr_eq(&found, &root));
+
+        // the starting node itself is skipped
+        assert!(Node::nearest_ancestor(&leaf, |value| *value == 4).is_none());
+        assert!(Node::nearest_ancestor(&root, |_| true).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn for_each_child_value_mut() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        Node::for_each_child_value_mut(&root, |value| *value += 10)?;
+        assert!(Node::values_equal(&root, &[1, 12, 13, 4, 5]));
+
+        let leaf = root.borrow().children()[0].clone();
+        assert_eq!(
+            Node::for_each_child_value_mut(&leaf, |value| *value += 1).err(),
+            Some(NodeError::NotAParent)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn path_values() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        let right = root.borrow().children()[1].clone();
+        let leaf = Node::insert(&right.borrow().children()[1].clone(), 6)?;
+        assert_eq!(Node::path_values(&leaf), vec![1, 3, 5, 6]);
+        assert_eq!(Node::path_values(&Node::parent(7)), vec![7]);
+        Ok(())
+    }
+
+    #[test]
+    fn splice_out() -> Result<(), NodeError> {
+        let root = Node::parent(0);
+        let _ = Node::insert(&root, 1)?;
+        let middle = Node::insert(&root, 2)?;
+        let _ = Node::insert(&root, 3)?;
+        let _ = Node::insert(&middle, 4)?;
+        let _ = Node::insert(&middle, 5)?;
+
+        Node::splice_out(&middle)?;
+        assert_eq!(Node::child_values(&root), vec![1, 4, 5, 3]);
+        for child in root.borrow().children() {
+            assert_parent_eq(&parent_of(child), &root);
+        }
+        assert!(middle.borrow().is_root());
+        assert!(!middle.borrow().has_children());
+
+        assert_eq!(Node::splice_out(&root).err(), Some(NodeError::CannotReplaceRoot));
+        Ok(())
+    }
+
+    #[test]
+    fn ref_is_leaf_and_root() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        let leaf = root.borrow().children()[0].clone();
+        let internal = root.borrow().children()[1].clone();
+
+        assert!(Node::ref_is_root(&root) && !Node::ref_is_leaf(&root));
+        assert!(!Node::ref_is_root(&leaf) && Node::ref_is_leaf(&leaf));
+        assert!(!Node::ref_is_root(&internal) && !Node::ref_is_leaf(&internal));
+        Ok(())
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn index_by_value() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        let index = Node::index_by_value(&root);
+        assert_eq!(index.len(), 5);
+        for node in Node::iter(root.clone()) {
+            assert!(NodeRef::ptr_eq(&index[node.borrow().value()], &node));
+        }
+
+        // later duplicates keep pointing at the first node
+        let first = root.borrow().children()[0].clone();
+        let _ = Node::insert(&first, 3)?;
+        let right = root.borrow().children()[1].clone();
+        assert!(NodeRef::ptr_eq(&Node::index_by_value(&root)[&3], &right));
+        Ok(())
+    }
+
+    #[test]
+    fn walk() -> Result<(), NodeError> {
+        use core::cell::RefCell;
+
+        let root = sample_tree()?;
+        let events = RefCell::new(Vec::new());
+        Node::walk(
+            &root,
+            |node, depth| events.borrow_mut().push((true, *node.borrow().value(), depth)),
+            |node, depth| events.borrow_mut().push((false, *node.borrow().value(), depth)),
+        );
+        assert_eq!(
+            events.into_inner(),
+            vec![
+                (true, 1, 0),
+                (true, 2, 1),
+                (false, 2, 1),
+                (true, 3, 1),
+                (true, 4, 2),
+                (false, 4, 2),
+                (true, 5, 2),
+                (false, 5, 2),
+                (false, 3, 1),
+                (false, 1, 0),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn reduce_values() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        let max = Node::reduce_values(&root, |max, value| max.max(*value));
+        assert_eq!(max, Some(5));
+
+        let lone = Node::parent(7);
+        assert_eq!(Node::reduce_values(&lone, |max, value| max.max(*value)), Some(7));
+        Ok(())
+    }
+
+    #[test]
+    fn clone_as_root() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        let branch = root.borrow().children()[1].clone();
+
+        let copy = Node::clone_as_root(&branch);
+        assert!(copy.borrow().is_root());
+        assert!(Node::values_equal(&copy, &[3, 4, 5]));
+        assert!(!NodeRef::ptr_eq(&copy.borrow().children()[0], &branch.borrow().children()[0]));
+        assert!(!branch.borrow().is_root());
+
+        let leaf = root.borrow().children()[0].clone();
+        assert!(Node::clone_as_root(&leaf).borrow().is_root());
+        Ok(())
+    }
+
+    #[test]
+    fn node_iter_peek() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        let mut nodes = Node::iter(root);
+        while let Some(peeked) = nodes.peek().cloned() {
+            let next = nodes.next().unwrap();
+            assert!(NodeRef::ptr_eq(&peeked, &next));
+        }
+        assert!(nodes.peek().is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn degree_sequence() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        assert_eq!(Node::degree_sequence(&root), vec![2, 0, 2, 0, 0]);
+        Ok(())
+    }
+
+    #[test]
+    fn from_sequences() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        let values: Vec<u8> = Node::iter_map(root.clone(), |value| *value).collect();
+        let degrees = Node::degree_sequence(&root);
+
+        let copy = Node::from_sequences(&values, &degrees)?;
+        assert!(Node::is_isomorphic(&root, &copy));
+        assert!(Node::values_equal(&copy, &values));
+        let branch = copy.borrow().children()[1].clone();
+        assert_parent_eq(&parent_of(&branch.borrow().children()[0]), &branch);
+
+        for (values, degrees) in [
+            (&[1, 2][..], &[2, 0][..]),
+            (&[1, 2, 3][..], &[1, 0, 0][..]),
+            (&[1, 2, 3][..], &[0, 1, 0][..]),
+            (&[1, 2][..], &[1][..]),
+            (&[1, 2, 3][..], &[1, usize::MAX, 0][..]),
+            (&[][..], &[][..]),
+        ] {
+            assert_eq!(
+                Node::from_sequences(values, degrees).err(),
+                Some(NodeError::MalformedSequence)
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn propagate() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        let leaves: Vec<_> = Node::iter(root.clone())
+            .filter(|node| node.borrow().is_leaf())
+            .collect();
+        let path_sums: Vec<u8> = leaves
+            .iter()
+            .map(|leaf| Node::path_values(leaf).iter().sum())
+            .collect();
+
+        Node::propagate(&root, |parent, child| *child += *parent);
+        assert_eq!(*root.borrow().value(), 1);
+        let values: Vec<u8> = leaves.iter().map(|leaf| *leaf.borrow().value()).collect();
+        assert_eq!(values, path_sums);
+        assert_eq!(values, vec![3, 8, 9]);
+        Ok(())
+    }
+
+    #[test]
+    fn count_edges_where() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        let right = root.borrow().children()[1].clone();
+        let _ = Node::insert(&right, 0)?;
+
+        assert_eq!(Node::count_edges_where(&root, |parent, child| child > parent), 4);
+        assert_eq!(Node::count_edges_where(&root, |parent, child| child < parent), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn longest_path() -> Result<(), NodeError> {
+        // 1 -> (2 -> 6 -> 7, 3 -> (4, 5 -> 8 -> 9 -> 10))
+        let root = sample_tree()?;
+        let mut node = root.borrow().children()[0].clone();
+        for value in 6..=7 {
+            node = Node::insert(&node, value)?;
+        }
+        let mut end = root.borrow().children()[1].borrow().children()[1].clone();
+        for value in 8..=10 {
+            end = Node::insert(&end, value)?;
+        }
+
+        let path = Node::longest_path(&root);
+        assert_eq!(path.len(), 6);
+        assert!(NodeRef::ptr_eq(&path[0], &root));
+        assert!(NodeRef::ptr_eq(&path[5], &end));
+
+        // ties go to the leftmost path
+        let tied = sample_tree()?;
+        let path = Node::longest_path(&tied);
+        assert_eq!(*path[2].borrow().value(), 4);
+
+        // a subtree's path starts at its own root
+        let left = root.borrow().children()[0].clone();
+        let path = Node::longest_path(&left);
+        assert_eq!(path.len(), 3);
+        assert!(NodeRef::ptr_eq(&path[0], &left));
+        assert_eq!(Node::longest_path(&Node::parent(0)).len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn iter_rev_bfs() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        let values: Vec<u8> = Node::iter_rev_bfs(root)
+            .map(|node| *node.borrow().value())
+            .collect();
+        assert_eq!(values, vec![5, 4, 3, 2, 1]);
+        Ok(())
+    }
+
+    #[test]
+    fn index_of_child_value() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        let _ = Node::insert(&root, 3)?;
+        assert_eq!(root.borrow().index_of_child_value(&3), Some(1));
+        assert_eq!(root.borrow().index_of_child_value(&4), None);
+        Ok(())
+    }
+
+    #[test]
+    fn iter_with_parent() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        let pairs: Vec<(Option<u8>, u8)> = Node::iter_with_parent(root.clone())
+            .map(|(parent, node)| (parent.map(|p| *p.borrow().value()), *node.borrow().value()))
+            .collect();
+        assert_eq!(pairs, vec![(None, 1), (Some(1), 2), (Some(1), 3), (Some(3), 4), (Some(3), 5)]);
+
+        for (parent, node) in Node::iter_with_parent(root).skip(1) {
+            assert_parent_eq(&parent_of(&node), &parent.unwrap());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn collapse_chains() -> Result<(), NodeError> {
+        let root = Node::parent(1u32);
+        let mut node = root.clone();
+        for value in [2, 3] {
+            node = Node::insert(&node, value)?;
+        }
+        assert_eq!(Node::collapse_chains(&root, |parent, child| *parent += *child), 2);
+        assert_eq!(*root.borrow().value(), 6);
+        assert!(!root.borrow().has_children());
+
+        // 1 -> 2 -> 3 -> (4, 5 -> 6)
+        let root = Node::parent(1u32);
+        let two = Node::insert(&root, 2)?;
+        let three = Node::insert(&two, 3)?;
+        let _ = Node::insert(&three, 4)?;
+        let five = Node::insert(&three, 5)?;
+        let _ = Node::insert(&five, 6)?;
+
+        assert_eq!(Node::collapse_chains(&root, |parent, child| *parent = *child), 3);
+        assert!(Node::values_equal(&root, &[3, 4, 6]));
+        for child in root.borrow().children() {
+            assert_parent_eq(&parent_of(child), &root);
+            assert!(child.borrow().is_leaf());
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn subtree_hash() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        let copy = Node::deep_clone(&root);
+        assert_eq!(Node::subtree_hash(&root), Node::subtree_hash(&copy));
+
+        let right = root.borrow().children()[1].clone();
+        let lone = Node::clone_as_root(&right);
+        assert_eq!(Node::subtree_hash(&right), Node::subtree_hash(&lone));
+
+        // same values in a different shape
+        let reshaped = Node::from_sequences(&[1, 2, 3, 4, 5], &[2, 2, 0, 0, 0])?;
+        assert_ne!(Node::subtree_hash(&root), Node::subtree_hash(&reshaped));
+
+        *copy.borrow().children()[0].borrow_mut().value_mut() = 9;
+        assert_ne!(Node::subtree_hash(&root), Node::subtree_hash(&copy));
+        Ok(())
+    }
+
+    #[test]
+    fn insert_before_and_after() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        let first = root.borrow().children()[0].clone();
+        let last = root.borrow().children()[1].clone();
+
+        let before = Node::insert_before(&first, 6)?;
+        let after = Node::insert_after(&last, 7)?;
+        assert_eq!(Node::child_values(&root), vec![6, 2, 3, 7]);
+        assert_parent_eq(&parent_of(&before), &root);
+        assert_parent_eq(&parent_of(&after), &root);
+
+        assert_eq!(Node::insert_after(&root, 8).err(), Some(NodeError::ParentNodeNotFound));
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn insert_before_and_after_notify() -> Result<(), NodeError> {
+        use crate::observer::TreeObserver;
+        use std::{cell::RefCell, rc::Rc};
+
+        #[derive(Default)]
+        struct Inserted(RefCell<Vec<u8>>);
+
+        impl TreeObserver<u8> for Inserted {
+            fn on_insert(&self, _parent: &NodeRef<u8>, child: &NodeRef<u8>) {
+                self.0.borrow_mut().push(*child.borrow().value());
+            }
+        }
+
+        let root = sample_tree()?;
+        let inserted = Rc::new(Inserted::default());
+        Node::set_observer(&root, inserted.clone())?;
+        let first = root.borrow().children()[0].clone();
+        let _ = Node::insert_before(&first, 6)?;
+        let _ = Node::insert_after(&first, 7)?;
+        assert_eq!(*inserted.0.borrow(), vec![6, 7]);
+        Ok(())
+    }
+
+    #[test]
+    fn for_each_level_mut() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        let mut levels = Vec::new();
+        Node::for_each_level_mut(&root, |depth, nodes| {
+            levels.push((depth, nodes.len()));
+            for node in nodes {
+                *node.borrow_mut().value_mut() *= 10;
+            }
+        });
+        assert_eq!(levels, vec![(0, 1), (1, 2), (2, 2)]);
+        assert!(Node::values_equal(&root, &[10, 20, 30, 40, 50]));
+        Ok(())
+    }
+
+    #[test]
+    fn is_subtree_of() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        let needle = Node::parent(3);
+        let _ = Node::insert(&needle, 4)?;
+        let last = Node::insert(&needle, 5)?;
+        assert!(Node::is_subtree_of(&needle, &root));
+        assert!(Node::is_subtree_of(&root, &root));
+
+        // same values in a different shape, or a different value
+        let _ = Node::insert(&last, 6)?;
+        assert!(!Node::is_subtree_of(&needle, &root));
+        assert!(!Node::is_subtree_of(&Node::parent(9), &root));
+        Ok(())
+    }
+
+    #[test]
+    fn parent_with_children() -> Result<(), NodeError> {
+        let right = Node::parent_with_children(3, vec![Node::leaf(4, None), Node::leaf(5, None)]);
+        let root = Node::parent_with_children(1, vec![Node::leaf(2, None), right.clone()]);
+
+        assert!(Node::value_eq(&root, &sample_tree()?));
+        assert!(root.borrow().is_root());
+        assert_parent_eq(&parent_of(&right), &root);
+        for child in right.borrow().children() {
+            assert_parent_eq(&parent_of(child), &right);
+        }
+
+        let empty = Node::parent_with_children(0, vec![]);
+        assert!(empty.borrow().is_root() && !empty.borrow().has_children());
+        Ok(())
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn first_shared_value() -> Result<(), NodeError> {
+        let a = sample_tree()?;
+        let b = Node::parent_with_children(9, vec![Node::leaf(5, None), Node::leaf(3, None)]);
+        assert_eq!(Node::first_shared_value(&a, &b), Some(3));
+
+        let disjoint = Node::parent_with_children(7, vec![Node::leaf(8, None)]);
+        assert_eq!(Node::first_shared_value(&a, &disjoint), None);
+        Ok(())
+    }
+
+    #[test]
+    fn replace_values() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        assert_eq!(Node::replace_values(&root, |value| value % 2 == 0, 0), 2);
+        assert!(Node::values_equal(&root, &[1, 0, 3, 0, 5]));
+        assert_eq!(Node::replace_values(&root, |value| *value > 5, 0), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn remove_at_path() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        let right = root.borrow().children()[1].clone();
+
+        let removed = Node::remove_at_path(&root, &[1, 0])?;
+        assert_eq!(*removed.borrow().value(), 4);
+        assert!(removed.borrow().is_root());
+        assert!(Node::values_equal(&root, &[1, 2, 3, 5]));
+
+        let _ = Node::remove_at_path(&root, &[1, 0])?;
+        assert!(right.borrow().is_leaf());
+
+        assert_eq!(Node::remove_at_path(&root, &[]).err(), Some(NodeError::CannotReplaceRoot));
+        assert_eq!(
+            Node::remove_at_path(&root, &[2]).err(),
+            Some(NodeError::IndexOutOfBounds { index: 2, len: 2 })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn node_iter_reset() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        let mut nodes = Node::iter(root);
+        assert_eq!(nodes.by_ref().count(), 5);
+
+        let other = Node::parent_with_children(7, vec![Node::leaf(8, None)]);
+        nodes.reset(other);
+        let values: Vec<u8> = nodes.map(|node| *node.borrow().value()).collect();
+        assert_eq!(values, vec![7, 8]);
+        Ok(())
+    }
+
+    #[test]
+    fn values_in_range() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        let values: Vec<u8> = Node::values_in_range(&root, &2, &4)
+            .iter()
+            .map(|node| *node.borrow().value())
+            .collect();
+        assert_eq!(values, vec![2, 3, 4]);
+        assert!(Node::values_in_range(&root, &6, &9).is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn structure_map() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        let degrees = Node::structure_map(&root, |node| node.borrow().degree());
+        assert!(Node::is_isomorphic(&root, &degrees));
+        assert!(Node::values_equal(&degrees, &[2, 0, 2, 0, 0]));
+        assert!(Node::values_equal(&root, &[1, 2, 3, 4, 5]));
+        Ok(())
+    }
+
+    #[test]
+    fn peel_leaves() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        let peeled: Vec<u8> = Node::peel_leaves(&root)
+            .iter()
+            .map(|node| *node.borrow().value())
+            .collect();
+        assert_eq!(peeled, vec![2, 4, 5]);
+        assert!(Node::values_equal(&root, &[1, 3]));
+        assert!(root.borrow().children()[0].borrow().is_leaf());
+
+        assert_eq!(Node::peel_leaves(&root).len(), 1);
+        assert!(Node::peel_leaves(&root).is_empty());
+        assert!(root.borrow().is_root());
+        Ok(())
+    }
+
+    #[test]
+    fn empty_tree() -> Result<(), NodeError> {
+        let empty: Tree<u8> = Tree::new();
+        assert!(empty.is_empty());
+        assert_eq!(empty.len(), 0);
+        assert_eq!(empty.root().err(), Some(NodeError::EmptyTree));
+        assert_eq!(empty.insert(1).err(), Some(NodeError::EmptyTree));
+
+        let tree = Tree::with_root(1);
+        assert!(!tree.is_empty());
+        let child = tree.insert(2)?;
+        assert_parent_eq(&parent_of(&child), tree.root()?);
+        assert_eq!(tree.len(), 2);
+
+        let root = sample_tree()?;
+        let wrapped = Tree::try_from(root.clone())?;
+        assert_eq!(wrapped.len(), 5);
+        assert!(NodeRef::ptr_eq(wrapped.clone().root()?, &root));
+
+        let child = root.borrow().children()[0].clone();
+        assert_eq!(Tree::try_from(child).err(), Some(NodeError::ExpectedARootNode));
+        Ok(())
+    }
+
+    #[test]
+    fn depth_from_root() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        let right = root.borrow().children()[1].clone();
+        let leaf = right.borrow().children()[1].clone();
+
+        assert_eq!(Node::depth_from_root(&root, &root), Some(0));
+        assert_eq!(Node::depth_from_root(&root, &leaf), Some(2));
+        assert_eq!(Node::depth_from_root(&right, &leaf), Some(1));
+        assert_eq!(Node::depth_from_root(&root, &Node::parent(9)), None);
+        assert_eq!(Node::depth_from_root(&right, &root), None);
+        Ok(())
+    }
+
+    #[test]
+    fn for_each_ancestor_mut() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        let right = root.borrow().children()[1].clone();
+        let leaf = Node::insert(&right.borrow().children()[0].clone(), 6)?;
+
+        Node::for_each_ancestor_mut(&leaf, |value| *value += 10);
+        assert_eq!(*root.borrow().value(), 11);
+        assert_eq!(Node::path_values(&leaf), vec![11, 13, 14, 6]);
+        assert_eq!(*root.borrow().children()[0].borrow().value(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn build_balanced() {
+        let root = Node::build_balanced(&[1, 2, 3, 4, 5, 6, 7], 2).unwrap();
+        assert!(Node::is_perfect(&root, 2));
+        assert_eq!(Node::count_at_depth(&root, 2), 4);
+        assert!(Node::values_equal(&root, &[1, 2, 3, 4, 5, 6, 7]));
+
+        assert!(Node::<i32>::build_balanced(&[], 2).is_none());
+        assert!(Node::build_balanced(&[1, 2], 0).is_none());
+
+        let root = Node::build_balanced(&[1, 2, 3], usize::MAX).unwrap();
+        assert_eq!(root.borrow().degree(), 2);
+        assert!(Node::values_equal(&root, &[1, 2, 3]));
+    }
+
+    #[test]
+    fn retain_map() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        let right = root.borrow().children()[1].clone();
+
+        // drop 2 and 4, double everything else
+        assert!(Node::retain_map(&root, |value| (value % 2 == 1).then(|| value * 2)));
+        assert!(Node::values_equal(&root, &[2, 6, 10]));
+        assert_parent_eq(&parent_of(&right), &root);
+        assert_eq!(right.borrow().children().len(), 1);
+
+        assert!(!Node::retain_map(&root, |_| None));
+        assert!(root.borrow().children().is_empty());
+        assert_eq!(*root.borrow().value(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn child_refs() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        for child in Node::child_refs(&root) {
+            // the parent is free to be borrowed mutably while iterating
+            let _ = Node::insert(&root, 0)?;
+            *child.borrow_mut().value_mut() *= 10;
+        }
+        assert!(Node::values_equal(&root, &[1, 20, 30, 0, 0, 4, 5]));
+        assert_eq!(Node::child_refs(&root.borrow().children()[0].clone()).count(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn path_exists() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        assert!(Node::path_exists(&root, &[]));
+        assert!(Node::path_exists(&root, &[1, 1]));
+        assert!(!Node::path_exists(&root, &[0, 0]));
+        assert!(!Node::path_exists(&root, &[2]));
+        assert!(!Node::path_exists(&root, &[1, 1, 0]));
+        Ok(())
+    }
+
+    #[test]
+    fn post_order_with_depth() -> Result<(), NodeError> {
+        let root = sample_tree()?;
+        let order = Node::post_order_with_depth(&root);
+        assert_eq!(order.len(), 5);
+
+        let (depth, last) = order.last().unwrap();
+        assert_eq!(*depth, 0);
+        assert_eq!(last.as_ptr(), root.as_ptr());
+
+        for (i, (depth, node)) in order.iter().enumerate() {
+            for child in node.borrow().children() {
+                let j = order.iter().position(|(_, n)| n.as_ptr() == child.as_ptr()).unwrap();
+                assert!(j < i);
+                assert_eq!(order[j].0, depth + 1);
+            }
+        }
+        let values: Vec<u8> = order.iter().map(|(_, n)| *n.borrow().value()).collect();
+        assert_eq!(values, vec![2, 4, 5, 3, 1]);
+        Ok(())
+    }
+
+    #[test]
+    fn insert_indexed() -> Result<(), NodeError> {
+        let root = Node::parent(1);
+        for expected in 0..3 {
+            let (index, child) = Node::insert_indexed(&root, expected + 10)?;
+            assert_eq!(index, expected);
+            assert_eq!(root.borrow().children()[index].as_ptr(), child.as_ptr());
+        }
+
+        let leaf = root.borrow().children()[0].clone();
+        let (index, _) = Node::insert_indexed(&leaf, 20)?;
+        assert_eq!(index, 0);
+        assert!(leaf.borrow().has_children());
+        Ok(())
+    }
 }
@@ -1,11 +1,15 @@
 #![allow(dead_code)]
 use crate::error::NodeError;
+use crate::observer::TreeObserver;
+#[cfg(feature = "std")]
+use crate::observer::{self, ObserverRef};
 
 #[cfg(not(feature = "std"))]
 use core::{
     cell::RefCell,
     fmt,
     fmt::{Debug, Display},
+    hash::{Hash, Hasher},
     iter::Iterator,
     mem,
 };
@@ -15,16 +19,24 @@ use rclite::Rc;
 use tracing::instrument;
 
 #[cfg(not(feature = "std"))]
-use alloc::{self, vec::Vec};
+use alloc::{
+    self,
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    string::String,
+    vec::{self, Vec},
+};
 
 #[cfg(feature = "std")]
 use std::{
     cell::RefCell,
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
     fmt,
     fmt::{Debug, Display},
+    hash::{DefaultHasher, Hash, Hasher},
     iter::Iterator,
     mem,
     rc::{Rc, Weak},
+    vec,
 };
 
 /// A reference-counted, mutable reference to a `Node<T>`.
@@ -38,10 +50,18 @@ use std::{
 ///
 /// # Example:
 /// ```
+/// # use libcanopy::{Node, NodeRef};
 /// let node: NodeRef<i32> = Node::leaf(42, None);
 /// ```
 pub type NodeRef<T> = Rc<RefCell<Node<T>>>;
 
+/// A non-owning handle to a `Node<T>`, which does not keep the node alive.
+///
+/// Useful for caches that should not extend the lifetime of the nodes they point to,
+/// [`Node::upgrade_ref`] returns `None` once every [`NodeRef`] to the node was dropped.
+#[cfg(feature = "std")]
+pub type WeakNodeRef<T> = Weak<RefCell<Node<T>>>;
+
 #[cfg(not(feature = "std"))]
 pub type ParentRc<T> = Rc<T>;
 
@@ -64,6 +84,7 @@ pub type ParentRc<T> = Weak<T>;
 ///
 /// # Example:
 /// ```
+/// # use libcanopy::{Node, NodeRef};
 /// let node: NodeRef<i32> = Node::leaf(42, None);
 /// ```
 pub type PrevNodeRef<T> = ParentRc<RefCell<Node<T>>>;
@@ -75,22 +96,27 @@ pub type PrevNodeRef<T> = ParentRc<RefCell<Node<T>>>;
 ///
 /// ### Creating a leaf Node
 /// ```
+/// # use libcanopy::Node;
 /// let node = Node::leaf(true, None);
-/// assert!(leaf.is_leaf());
+/// assert!(node.borrow().is_leaf());
 /// ```
 ///
 /// ### Creating a Root Node
 /// ```
+/// # use libcanopy::Node;
 /// let node = Node::parent(true);
-/// assert!(leaf.is_root());
+/// assert!(node.borrow().is_root());
 /// ```
 /// ### Link nodes together
 /// ```
-/// let node = Node::Parent { value : true,
-///                           prev : None,
-///                           next : vec![] };
+/// # use libcanopy::{Node, NodeRef, error::NodeError};
+/// # fn main() -> Result<(), NodeError> {
+/// let node: NodeRef<bool> = Node::Parent { value : true,
+///                                          prev : None,
+///                                          next : vec![] }.into();
 /// let _ = Node::insert(&node, false)?;
-///
+/// # Ok(())
+/// # }
 /// ```
 ///
 /// ## Layout
@@ -165,6 +191,25 @@ where
     }
 }
 
+/// Hashes the whole subtree rooted at the node, each value in pre-order along with its
+/// number of children, so trees of equal shape and values hash equally.
+impl<T> Hash for Node<T>
+where
+    T: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.value().hash(state);
+        self.degree().hash(state);
+        for child in self.children() {
+            for node in Node::iter_dfs(Rc::clone(child)) {
+                let n = node.borrow();
+                n.value().hash(state);
+                n.degree().hash(state);
+            }
+        }
+    }
+}
+
 impl<T> Node<T>
 where
     T: Debug,
@@ -180,6 +225,29 @@ where
         }))
     }
 
+    /// Create [`Node::Parent`] instance holding `children`, in order, each child pointing back at it.
+    /// An empty `children` still yields a [`Node::Parent`], i.e. a root without children.
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::Node;
+    /// let leaves = vec![Node::leaf(2, None), Node::leaf(3, None)];
+    /// let root = Node::parent_with_children(1, leaves);
+    /// assert_eq!(root.borrow().children().len(), 2);
+    /// ```
+    #[instrument(level = "trace")]
+    pub fn parent_with_children(value: T, children: Vec<NodeRef<T>>) -> NodeRef<T> {
+        let parent = Node::parent(value);
+        for child in &children {
+            Node::forget_observer(child);
+            child.borrow_mut().set_prev(Some(&parent));
+        }
+        if let Node::Parent { next, .. } = &mut *parent.borrow_mut() {
+            *next = children;
+        }
+        parent
+    }
+
     /// Create [`Node::Leaf`] instance
     #[inline]
     #[instrument(level = "trace")]
@@ -204,11 +272,15 @@ where
     ///
     /// # Example:
     /// ```
-    /// let mut leaf = Node::leaf(42, None);
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let leaf = Node::leaf(42, None);
     ///
     /// // insert child node into leaf to make it parent.
     /// let child = Node::leaf(100, Some(leaf.clone()));
     /// Node::upgrade(&leaf, &child)?;
+    /// # Ok(())
+    /// # }
     /// ```
     #[inline]
     #[instrument(level = "trace")]
@@ -216,10 +288,65 @@ where
     where
         T: Debug + Default + Clone,
     {
-        parent.borrow_mut().upgrade_inner(node)
+        Node::forget_observer(node);
+        parent.borrow_mut().upgrade_inner(vec![Rc::clone(node)])?;
+        Node::notify(parent, |observer| observer.on_insert(parent, node));
+        Ok(())
+    }
+
+    /// Converts a [`Node::Leaf`] node into a [`Node::Parent`] holding all of `children` at once,
+    /// pointing each child's parent at `leaf`. Children still linked beneath another node are
+    /// detached from it first, as [`Node::split_off`] would.
+    ///
+    /// ### Parameters
+    /// - `leaf`: A reference-counted node that will be converted.
+    /// - `children`: The nodes that become its children, in order.
+    ///
+    /// ### Return
+    /// - Result where on success empty tuple, [`NodeError::ExpectedChildren`] if `children` is empty,
+    ///   [`NodeError::ParentUpgradeNotAllowed`] if the node is already a [`Node::Parent`],
+    ///   and [`NodeError::CycleDetected`] if one of `children` is `leaf` or one of its ancestors.
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let leaf = Node::leaf(1, None);
+    /// let children = vec![Node::leaf(2, None), Node::leaf(3, None)];
+    /// Node::upgrade_with_children(&leaf, children)?;
+    /// assert_eq!(leaf.borrow().children().len(), 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrument(level = "trace")]
+    pub fn upgrade_with_children(leaf: &NodeRef<T>, children: Vec<NodeRef<T>>) -> Result<(), NodeError>
+    where
+        T: Default + Clone,
+    {
+        if children.is_empty() {
+            return Err(NodeError::ExpectedChildren);
+        }
+        if !leaf.borrow().is_leaf() {
+            return Err(NodeError::ParentUpgradeNotAllowed);
+        }
+        if children.iter().any(|child| Node::is_within(child, leaf)) {
+            return Err(NodeError::CycleDetected);
+        }
+        for child in &children {
+            Node::detach(child)?;
+        }
+        leaf.borrow_mut().upgrade_inner(children.clone())?;
+        for child in &children {
+            Node::forget_observer(child);
+            child.borrow_mut().set_prev(Some(leaf));
+        }
+        for child in &children {
+            Node::notify(leaf, |observer| observer.on_insert(leaf, child));
+        }
+        Ok(())
     }
 
-    fn upgrade_inner(&mut self, node: &NodeRef<T>) -> Result<(), NodeError>
+    fn upgrade_inner(&mut self, next: Vec<NodeRef<T>>) -> Result<(), NodeError>
     where
         T: Default + Clone,
     {
@@ -231,7 +358,7 @@ where
                 *self = Self::Parent {
                     value: leaf_value,
                     prev,
-                    next: vec![Rc::clone(node)],
+                    next,
                 };
                 Ok(())
             }
@@ -251,13 +378,17 @@ where
     ///
     /// ### Example:
     /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
     /// let root = Node::parent(42);
-    /// let child = Node::insert(&root, 69);
+    /// let child = Node::insert(&root, 69)?;
     /// // upgrades child from leaf -> parent
-    /// let gc = Node::insert(&child, 420);
+    /// let gc = Node::insert(&child, 420)?;
     /// // By poping childs only child node will
     /// // auto switched to Leaf
     /// Node::pop(&child, &gc)?;
+    /// # Ok(())
+    /// # }
     /// ```
     #[inline]
     #[instrument(level = "trace")]
@@ -294,6 +425,135 @@ where
             _ => Err(NodeError::DowngradeNotParent), // Assuming you have this error variant
         }
     }
+
+    /// Reserves capacity for at least `additional` more children within a [`Node::Parent`].
+    ///
+    /// A [`Node::Leaf`] has no child list to reserve into, and is not upgraded since
+    /// there is no child to upgrade it with.
+    ///
+    /// ### Parameters
+    /// - `parent`: A refrence to the node that will hold the children.
+    /// - `additional`: The number of children expected to be inserted.
+    ///
+    /// ### Return
+    /// - Result of an empty tuple, or [`NodeError::NotAParent`] if `parent` is a [`Node::Leaf`].
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent(1);
+    /// Node::reserve(&root, 64)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    #[instrument(level = "trace")]
+    pub fn reserve(parent: &NodeRef<T>, additional: usize) -> Result<(), NodeError> {
+        match &mut *parent.borrow_mut() {
+            Self::Parent { next, .. } => {
+                next.reserve(additional);
+                Ok(())
+            }
+            Self::Leaf { .. } => Err(NodeError::NotAParent),
+        }
+    }
+
+    /// Registers `observer` to be notified of structural changes anywhere within the tree of `root`,
+    /// replacing any previously registered observer.
+    ///
+    /// Observers are kept in a table of the current thread rather than in the nodes, while no
+    /// tree has an observer registered, structural changes skip the lookup entirely.
+    /// See the [`observer`](crate::observer) module for which operations are reported.
+    ///
+    /// ### Return
+    /// - Result of an empty tuple, or [`NodeError::ExpectedARootNode`] if `root` is not a root.
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, NodeRef, error::NodeError, observer::TreeObserver};
+    /// # use std::{cell::Cell, rc::Rc};
+    /// # fn main() -> Result<(), NodeError> {
+    /// #[derive(Default)]
+    /// struct Counter(Cell<usize>);
+    ///
+    /// impl TreeObserver<u8> for Counter {
+    ///     fn on_insert(&self, _parent: &NodeRef<u8>, _child: &NodeRef<u8>) {
+    ///         self.0.set(self.0.get() + 1);
+    ///     }
+    /// }
+    ///
+    /// let root = Node::parent(1);
+    /// let counter = Rc::new(Counter::default());
+    /// Node::set_observer(&root, counter.clone())?;
+    /// let _ = Node::insert(&root, 2)?;
+    /// assert_eq!(counter.0.get(), 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "std")]
+    #[instrument(level = "trace", skip(observer))]
+    pub fn set_observer(root: &NodeRef<T>, observer: ObserverRef<T>) -> Result<(), NodeError>
+    where
+        T: 'static,
+    {
+        root.borrow().expect_root()?;
+        observer::register(root, observer);
+        Ok(())
+    }
+
+    /// Removes the observer registered on `root`, returning it.
+    #[cfg(feature = "std")]
+    #[instrument(level = "trace")]
+    pub fn clear_observer(root: &NodeRef<T>) -> Option<ObserverRef<T>> {
+        observer::unregister(root)
+    }
+
+    /// Swaps the value held by `root` for `value`, returning the previous one.
+    ///
+    /// ### Return
+    /// - Result of the old value, [`NodeError::ExpectedARootNode`] if `root` is not a root,
+    ///   or [`NodeError::AlreadyBorrowed`] if `root` is currently borrowed.
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent(1);
+    /// assert_eq!(Node::set_root_value(&root, 2)?, 1);
+    /// assert_eq!(*root.borrow().value(), 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrument(level = "trace")]
+    pub fn set_root_value(root: &NodeRef<T>, value: T) -> Result<T, NodeError> {
+        let mut r = root.try_borrow_mut()?;
+        r.expect_root()?;
+        Ok(mem::replace(r.value_mut(), value))
+    }
+
+    /// Points `node` at `new_parent`, or clears its parent with `None`.
+    ///
+    /// This is a low-level primitive, only the parent refrence of `node` is updated,
+    /// neither the old nor the new parent's children are touched. Keeping both sides
+    /// of the link consistent is up to the caller.
+    ///
+    /// ### Parameters
+    /// - `node`: A refrence to the node being reparented.
+    /// - `new_parent`: The node to point at, or `None` to detach.
+    ///
+    /// ### Return
+    /// - Result of an empty tuple, or [`NodeError::CycleDetected`] if `new_parent` is `node`
+    ///   or one of its descendants.
+    #[inline]
+    #[instrument(level = "trace")]
+    pub fn set_parent(node: &NodeRef<T>, new_parent: Option<&NodeRef<T>>) -> Result<(), NodeError> {
+        if new_parent.is_some_and(|parent| Node::is_within(node, parent)) {
+            return Err(NodeError::CycleDetected);
+        }
+        node.borrow_mut().set_prev(new_parent);
+        Ok(())
+    }
 }
 
 impl<T> Node<T> {
@@ -345,6 +605,30 @@ impl<T> Node<T> {
         }
     }
 
+    /// Borrow `node` and check whether it is a root, see [`Node::is_root`].
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent(1);
+    /// let children = [Node::insert(&root, 2)?, Node::insert(&root, 3)?];
+    /// assert!(Node::ref_is_root(&root));
+    /// assert_eq!(children.iter().filter(|c| Node::ref_is_leaf(c)).count(), 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn ref_is_root(node: &NodeRef<T>) -> bool {
+        node.borrow().is_root()
+    }
+
+    /// Borrow `node` and check whether it is a [`Node::Leaf`], see [`Node::is_leaf`].
+    #[inline]
+    pub fn ref_is_leaf(node: &NodeRef<T>) -> bool {
+        node.borrow().is_leaf()
+    }
+
     /// ### Return
     /// - `bool` that checks if Node instance has children
     #[inline]
@@ -355,6 +639,25 @@ impl<T> Node<T> {
         }
     }
 
+    /// ### Return
+    /// - Number of children held by the node, `0` for a [`Node::Leaf`].
+    #[inline]
+    pub fn degree(&self) -> usize {
+        self.children().len()
+    }
+
+    /// ### Return
+    /// - Index of the first child holding a value equal to `value`, `None` if there is no such child.
+    #[inline]
+    pub fn index_of_child_value(&self, value: &T) -> Option<usize>
+    where
+        T: PartialEq,
+    {
+        self.children()
+            .iter()
+            .position(|child| child.borrow().value() == value)
+    }
+
     /// ### Return
     /// - `&T` of the [`Node<T>`]
     #[inline]
@@ -365,6 +668,16 @@ impl<T> Node<T> {
         }
     }
 
+    /// ### Return
+    /// - `&mut T` of the [`Node<T>`]
+    #[inline]
+    pub fn value_mut(&mut self) -> &mut T {
+        match self {
+            Self::Parent { value, .. } => value,
+            Self::Leaf { value, .. } => value,
+        }
+    }
+
     /// ### Return
     /// - list of [`NodeRef<T>`]
     #[inline]
@@ -410,6 +723,201 @@ impl<T> Node<T> {
             Self::Leaf { prev, .. } => prev.clone().ok_or(NodeError::ParentNodeNotFound),
         }
     }
+
+    /// Strong refrence to the parent node, upgrading the [`Weak`] under `std`.
+    #[inline]
+    fn parent_ref(&self) -> Option<NodeRef<T>> {
+        #[cfg(feature = "std")]
+        return self.prev().and_then(|p| p.upgrade());
+        #[cfg(not(feature = "std"))]
+        return self.prev();
+    }
+
+    /// Point the node at a new parent, or detach it with `None`.
+    #[inline]
+    fn set_prev(&mut self, parent: Option<&NodeRef<T>>) {
+        match self {
+            Self::Parent { prev, .. } | Self::Leaf { prev, .. } => {
+                #[cfg(feature = "std")]
+                {
+                    *prev = parent.map(Rc::downgrade);
+                }
+                #[cfg(not(feature = "std"))]
+                {
+                    *prev = parent.cloned();
+                }
+            }
+        }
+    }
+
+    /// Check if `node` is `ancestor` itself or sits anywhere beneath it, by walking up parent refrences.
+    fn is_within(ancestor: &NodeRef<T>, node: &NodeRef<T>) -> bool {
+        let mut current = Some(Rc::clone(node));
+        while let Some(n) = current {
+            if Rc::ptr_eq(&n, ancestor) {
+                return true;
+            }
+            current = n.borrow().parent_ref();
+        }
+        false
+    }
+
+    /// Rebuild a tree from `(value, child count)` entries produced in pre-order by `next`,
+    /// stopping once every announced child was read.
+    pub(crate) fn from_pre_order<F>(mut next: F) -> Result<NodeRef<T>, NodeError>
+    where
+        F: FnMut() -> Result<(T, usize), NodeError>,
+    {
+        Node::from_pre_order_variants(|| next().map(|(value, count)| (value, count, false)))
+    }
+
+    /// Like [`Node::from_pre_order`], but every entry also tells whether its node is kept as a
+    /// [`Node::Parent`] when it has no children. The root is always a [`Node::Parent`].
+    fn from_pre_order_variants<E, F>(mut next: F) -> Result<NodeRef<T>, E>
+    where
+        F: FnMut() -> Result<(T, usize, bool), E>,
+    {
+        let (value, count, _) = next()?;
+        let root: NodeRef<T> = Node::Parent {
+            value,
+            prev: None,
+            next: vec![],
+        }
+        .into();
+
+        let mut stack = vec![(Rc::clone(&root), count)];
+        while let Some((parent, remaining)) = stack.last_mut() {
+            if *remaining == 0 {
+                stack.pop();
+                continue;
+            }
+            *remaining -= 1;
+
+            let (value, count, is_parent) = next()?;
+            let node: NodeRef<T> = if count == 0 && !is_parent {
+                Node::Leaf { value, prev: None }.into()
+            } else {
+                Node::Parent {
+                    value,
+                    prev: None,
+                    next: vec![],
+                }
+                .into()
+            };
+            node.borrow_mut().set_prev(Some(parent));
+            if let Node::Parent { next, .. } = &mut *parent.borrow_mut() {
+                next.push(Rc::clone(&node));
+            }
+            if count != 0 {
+                stack.push((node, count));
+            }
+        }
+        Ok(root)
+    }
+
+    /// Build a new tree with the same shape as `root`, taking each value from `value`.
+    /// Every [`Node::Leaf`] and [`Node::Parent`] keeps its variant, and children are
+    /// added in reverse order when `reverse` is set.
+    fn copy_tree<U, F>(root: &NodeRef<T>, mut value: F, reverse: bool) -> NodeRef<U>
+    where
+        F: FnMut(&NodeRef<T>) -> U,
+    {
+        let mut copy = None;
+        let mut queue = VecDeque::from([(Rc::clone(root), None::<NodeRef<U>>)]);
+        while let Some((source, parent)) = queue.pop_front() {
+            let node: NodeRef<U> = if source.borrow().is_leaf() {
+                Node::Leaf {
+                    value: value(&source),
+                    prev: None,
+                }
+                .into()
+            } else {
+                Node::Parent {
+                    value: value(&source),
+                    prev: None,
+                    next: vec![],
+                }
+                .into()
+            };
+            match parent {
+                Some(parent) => {
+                    node.borrow_mut().set_prev(Some(&parent));
+                    if let Node::Parent { next, .. } = &mut *parent.borrow_mut() {
+                        next.push(Rc::clone(&node));
+                    }
+                }
+                None => copy = Some(Rc::clone(&node)),
+            }
+
+            let s = source.borrow();
+            let children = s.children().iter().map(|child| (Rc::clone(child), Some(Rc::clone(&node))));
+            if reverse {
+                queue.extend(children.rev());
+            } else {
+                queue.extend(children);
+            }
+        }
+        copy.expect("the root is always copied first")
+    }
+
+    /// Walk up parent refrences to the topmost node of `node`'s tree.
+    fn root_of(node: &NodeRef<T>) -> NodeRef<T> {
+        let mut root = Rc::clone(node);
+        loop {
+            let parent = root.borrow().parent_ref();
+            match parent {
+                Some(parent) => root = parent,
+                None => return root,
+            }
+        }
+    }
+
+    /// Every node from the topmost ancestor down to `node` itself, following parent refrences.
+    fn lineage(node: &NodeRef<T>) -> Vec<NodeRef<T>> {
+        let mut lineage = vec![Rc::clone(node)];
+        loop {
+            let parent = lineage[lineage.len() - 1].borrow().parent_ref();
+            match parent {
+                Some(parent) => lineage.push(parent),
+                None => break,
+            }
+        }
+        lineage.reverse();
+        lineage
+    }
+
+    /// Run `f` with the observer registered on the root of `node`'s tree, if there is one.
+    fn notify<F>(node: &NodeRef<T>, f: F)
+    where
+        F: FnOnce(&dyn TreeObserver<T>),
+    {
+        #[cfg(feature = "std")]
+        if observer::is_observing()
+            && let Some(observer) = observer::observer_of(&Node::root_of(node))
+        {
+            f(&*observer);
+        }
+        #[cfg(not(feature = "std"))]
+        let _ = (node, f);
+    }
+
+    /// Drops the observer of `node`, which is about to be linked beneath another node
+    /// and so stops being a root.
+    #[inline]
+    fn forget_observer(node: &NodeRef<T>) {
+        #[cfg(feature = "std")]
+        if observer::is_observing() {
+            observer::unregister(node);
+        }
+        #[cfg(not(feature = "std"))]
+        let _ = node;
+    }
+
+    /// Position of `child` within the node's children by pointer identity.
+    #[inline]
+    fn position_of(&self, child: &NodeRef<T>) -> Option<usize> {
+        self.children().iter().position(|c| Rc::ptr_eq(c, child))
+    }
 }
 
 impl<T> Node<T>
@@ -427,106 +935,408 @@ where
     ///
     /// ### Example
     /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
     /// let root = Node::parent(1);
-    /// let child1 = Node::insert(root, 2)?;
-    /// let _ = Node::insert(child1, 3)?;
-    /// let _ = Node::insert(child1, 4)?;
+    /// let child1 = Node::insert(&root, 2)?;
+    /// let _ = Node::insert(&child1, 3)?;
+    /// let _ = Node::insert(&child1, 4)?;
+    /// # Ok(())
+    /// # }
     /// ```
     #[instrument(level = "info")]
     pub fn insert(parent: &NodeRef<T>, value: T) -> Result<NodeRef<T>, NodeError> {
         let node = Node::leaf(value, Some(Rc::clone(parent)));
         Node::inner_insert(parent, &node)?;
+        Node::notify(parent, |observer| observer.on_insert(parent, &node));
         Ok(node)
     }
 
-    /// Insert [`NodeRef`] within the prarent [`NodeRef`]`
-    ///
-    /// ### Parameters
-    /// - `parent`: A refrence to the Node to which will add child to.
-    /// - `node`: A referecne to child node.
+    /// Like [`Node::insert`], but also returns the index at which the new child landed
+    /// among the children of `parent`, `0` when a [`Node::Leaf`] parent gets upgraded.
     ///
     /// ### Return
-    /// - Result of a empty tuple, or [`NodeError`]
+    /// - Result of the child's index paired with a [`NodeRef<T>`] to it, or [`NodeError`].
     ///
     /// ### Example
     /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
     /// let root = Node::parent(1);
-    /// // allocat memeory for child with no parent
-    /// let child = Node::leaf(2, None);
-    ///
-    /// // insert the child into the parent root node
-    /// let _ = Node::insert_node(&root, &child)?;
+    /// let _ = Node::insert(&root, 2)?;
+    /// let (index, child) = Node::insert_indexed(&root, 3)?;
+    /// assert_eq!(index, 1);
+    /// assert_eq!(*child.borrow().value(), 3);
+    /// # Ok(())
+    /// # }
     /// ```
     #[instrument(level = "info")]
-    #[cfg(feature = "std")]
-    pub fn insert_node(parent: &NodeRef<T>, node: &NodeRef<T>) -> Result<(), NodeError> {
-        let mut n = node.borrow_mut();
-        match &mut *n {
-            Node::Leaf { prev, .. } | Node::Parent { prev, .. } => {
-                *prev = Some(NodeRef::downgrade(parent));
-            }
-        }
-        Node::inner_insert(parent, node)?;
-        Ok(())
+    pub fn insert_indexed(parent: &NodeRef<T>, value: T) -> Result<(usize, NodeRef<T>), NodeError> {
+        let index = parent.borrow().degree();
+        let node = Node::insert(parent, value)?;
+        Ok((index, node))
     }
 
-    /// Insert [`NodeRef`] within the prarent [`NodeRef`]`
+    /// Insert [`Node`] with value T at the front of the [`Node`]'s children.
     ///
     /// ### Parameters
     /// - `parent`: A refrence to the Node to which will add child to.
-    /// - `node`: A referecne to child node.
+    /// - `value`: A generic value type.
     ///
     /// ### Return
-    /// - Result of a empty tuple, or [`NodeError`]
+    /// - Result of a [`NodeRef<T>`] to the newly inserted child node, or [`NodeError`].
     ///
     /// ### Example
     /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
     /// let root = Node::parent(1);
-    /// // allocat memeory for child with no parent
-    /// let child = Node::leaf(2, None);
-    ///
-    /// // insert the child into the parent root node
-    /// let _ = Node::insert_node(&root, &child)?;
+    /// let _ = Node::insert(&root, 2)?;
+    /// let _ = Node::prepend(&root, 3)?;
+    /// assert_eq!(*root.borrow().children()[0].borrow().value(), 3);
+    /// # Ok(())
+    /// # }
     /// ```
     #[instrument(level = "info")]
-    #[cfg(not(feature = "std"))]
-    pub fn insert_node(parent: &NodeRef<T>, node: &NodeRef<T>) -> Result<(), NodeError> {
-        let mut n = node.borrow_mut();
-        match &mut *n {
-            Node::Leaf { prev, .. } | Node::Parent { prev, .. } => {
-                *prev = Some(NodeRef::clone(parent));
-            }
-        }
-        Node::inner_insert(parent, node)?;
-        Ok(())
-    }
-
-    fn inner_insert(parent: &NodeRef<T>, node: &NodeRef<T>) -> Result<(), NodeError> {
-        let mut p = parent.borrow_mut();
-        // Get mutable access to the parent
-        match &mut *p {
-            Node::Leaf { .. } => {
-                drop(p);
-                // If parent is a leaf, upgrade it to a parent and add this node as a child
-                Node::upgrade(parent, node)?;
-            }
-            Node::Parent { next, .. } => {
-                // If parent is already a parent, just add this node to its children
-                next.push(Rc::clone(node));
-            }
-        }
-
-        // Return the new child node
-        Ok(())
+    pub fn prepend(parent: &NodeRef<T>, value: T) -> Result<NodeRef<T>, NodeError> {
+        let node = Node::leaf(value, Some(Rc::clone(parent)));
+        Node::inner_insert_at(parent, &node, 0)?;
+        Node::notify(parent, |observer| observer.on_insert(parent, &node));
+        Ok(node)
     }
 
-    /// Removes a child node from its parent [`Node::Parent`].
+    /// Insert [`Node`] with value T at `index` among the [`Node`]'s children.
     ///
     /// ### Parameters
     /// - `parent`: A refrence to the Node to which will add child to.
-    /// - `child`: A reference to the child node to be removed.
+    /// - `index`: The position the new child takes, at most the current child count.
+    /// - `value`: A generic value type.
     ///
-    /// ### Returns
+    /// ### Return
+    /// - Result of a [`NodeRef<T>`] to the newly inserted child node, or [`NodeError::IndexOutOfBounds`]
+    ///   if `index` exceeds the child count.
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent(1);
+    /// let _ = Node::insert(&root, 2)?;
+    /// let _ = Node::insert(&root, 4)?;
+    /// let _ = Node::insert_at(&root, 1, 3)?;
+    /// assert_eq!(*root.borrow().children()[1].borrow().value(), 3);
+    /// assert!(Node::insert_at(&root, 5, 5).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrument(level = "info")]
+    pub fn insert_at(parent: &NodeRef<T>, index: usize, value: T) -> Result<NodeRef<T>, NodeError> {
+        let node = Node::leaf(value, Some(Rc::clone(parent)));
+        Node::inner_insert_at(parent, &node, index)?;
+        Node::notify(parent, |observer| observer.on_insert(parent, &node));
+        Ok(node)
+    }
+
+    /// Insert [`Node`] with value T among the [`Node`]'s children, keeping them in ascending order.
+    ///
+    /// The position is found by binary search, so the existing children are expected to be sorted already.
+    /// Equal values are placed after the ones already present.
+    ///
+    /// ### Parameters
+    /// - `parent`: A refrence to the Node to which will add child to.
+    /// - `value`: A generic value type.
+    ///
+    /// ### Return
+    /// - Result of a [`NodeRef<T>`] to the newly inserted child node, or [`NodeError`].
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent(0);
+    /// let _ = Node::insert_sorted(&root, 3)?;
+    /// let _ = Node::insert_sorted(&root, 1)?;
+    /// assert_eq!(*root.borrow().children()[0].borrow().value(), 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrument(level = "info")]
+    pub fn insert_sorted(parent: &NodeRef<T>, value: T) -> Result<NodeRef<T>, NodeError>
+    where
+        T: Ord,
+    {
+        let index = parent
+            .borrow()
+            .children()
+            .partition_point(|child| *child.borrow().value() <= value);
+        let node = Node::leaf(value, Some(Rc::clone(parent)));
+        Node::inner_insert_at(parent, &node, index)?;
+        Node::notify(parent, |observer| observer.on_insert(parent, &node));
+        Ok(node)
+    }
+
+    /// Insert [`Node`] with value T right before `sibling` among its parent's children.
+    ///
+    /// ### Parameters
+    /// - `sibling`: A refrence to the child the new node is placed in front of.
+    /// - `value`: A generic value type.
+    ///
+    /// ### Return
+    /// - Result of a [`NodeRef<T>`] to the newly inserted node, or [`NodeError::ParentNodeNotFound`]
+    ///   if `sibling` is a root.
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent(0);
+    /// let child = Node::insert(&root, 2)?;
+    /// let _ = Node::insert_before(&child, 1)?;
+    /// assert_eq!(*root.borrow().children()[0].borrow().value(), 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrument(level = "info")]
+    pub fn insert_before(sibling: &NodeRef<T>, value: T) -> Result<NodeRef<T>, NodeError> {
+        Node::insert_beside(sibling, value, 0)
+    }
+
+    /// Insert [`Node`] with value T right after `sibling` among its parent's children.
+    ///
+    /// ### Parameters
+    /// - `sibling`: A refrence to the child the new node is placed behind.
+    /// - `value`: A generic value type.
+    ///
+    /// ### Return
+    /// - Result of a [`NodeRef<T>`] to the newly inserted node, or [`NodeError::ParentNodeNotFound`]
+    ///   if `sibling` is a root.
+    #[instrument(level = "info")]
+    pub fn insert_after(sibling: &NodeRef<T>, value: T) -> Result<NodeRef<T>, NodeError> {
+        Node::insert_beside(sibling, value, 1)
+    }
+
+    /// Insert a new leaf `offset` positions past `sibling` within its parent.
+    fn insert_beside(sibling: &NodeRef<T>, value: T, offset: usize) -> Result<NodeRef<T>, NodeError> {
+        let parent = sibling
+            .borrow()
+            .parent_ref()
+            .ok_or(NodeError::ParentNodeNotFound)?;
+        let index = parent
+            .borrow()
+            .position_of(sibling)
+            .ok_or(NodeError::ParentNodeNotFound)?;
+        let node = Node::leaf(value, Some(Rc::clone(&parent)));
+        Node::inner_insert_at(&parent, &node, index + offset)?;
+        Node::notify(&parent, |observer| observer.on_insert(&parent, &node));
+        Ok(node)
+    }
+
+    /// Move the child at `from` to position `to` among its siblings, shifting the ones in between.
+    /// Parent pointers are left untouched.
+    ///
+    /// ### Parameters
+    /// - `parent`: A refrence to the Node whose children are reordered.
+    /// - `from`: Current index of the child.
+    /// - `to`: Index the child ends up at.
+    ///
+    /// ### Return
+    /// - Result of a empty tuple, or [`NodeError::IndexOutOfBounds`] if either index is past the last child.
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent(0);
+    /// let _ = Node::insert(&root, 1)?;
+    /// let _ = Node::insert(&root, 2)?;
+    /// Node::reorder_child(&root, 1, 0)?;
+    /// assert_eq!(*root.borrow().children()[0].borrow().value(), 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrument(level = "info")]
+    pub fn reorder_child(parent: &NodeRef<T>, from: usize, to: usize) -> Result<(), NodeError> {
+        let mut p = parent.borrow_mut();
+        let Node::Parent { next, .. } = &mut *p else {
+            return Err(NodeError::IndexOutOfBounds { index: from, len: 0 });
+        };
+        let len = next.len();
+        if let Some(index) = [from, to].into_iter().find(|&index| index >= len) {
+            return Err(NodeError::IndexOutOfBounds { index, len });
+        }
+        let child = next.remove(from);
+        next.insert(to, child);
+        Ok(())
+    }
+
+    /// Insert [`NodeRef`] within the prarent [`NodeRef`]`
+    ///
+    /// ### Parameters
+    /// - `parent`: A refrence to the Node to which will add child to.
+    /// - `node`: A referecne to child node.
+    ///
+    /// ### Return
+    /// - Result of a empty tuple, or [`NodeError`]
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent(1);
+    /// // allocat memeory for child with no parent
+    /// let child = Node::leaf(2, None);
+    ///
+    /// // insert the child into the parent root node
+    /// let _ = Node::insert_node(&root, &child)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrument(level = "info")]
+    #[cfg(feature = "std")]
+    pub fn insert_node(parent: &NodeRef<T>, node: &NodeRef<T>) -> Result<(), NodeError> {
+        {
+            let mut n = node.borrow_mut();
+            match &mut *n {
+                Node::Leaf { prev, .. } | Node::Parent { prev, .. } => {
+                    *prev = Some(NodeRef::downgrade(parent));
+                }
+            }
+        }
+        Node::inner_insert(parent, node)?;
+        Node::notify(parent, |observer| observer.on_insert(parent, node));
+        Ok(())
+    }
+
+    /// Insert [`NodeRef`] within the prarent [`NodeRef`]`
+    ///
+    /// ### Parameters
+    /// - `parent`: A refrence to the Node to which will add child to.
+    /// - `node`: A referecne to child node.
+    ///
+    /// ### Return
+    /// - Result of a empty tuple, or [`NodeError`]
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent(1);
+    /// // allocat memeory for child with no parent
+    /// let child = Node::leaf(2, None);
+    ///
+    /// // insert the child into the parent root node
+    /// let _ = Node::insert_node(&root, &child)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrument(level = "info")]
+    #[cfg(not(feature = "std"))]
+    pub fn insert_node(parent: &NodeRef<T>, node: &NodeRef<T>) -> Result<(), NodeError> {
+        {
+            let mut n = node.borrow_mut();
+            match &mut *n {
+                Node::Leaf { prev, .. } | Node::Parent { prev, .. } => {
+                    *prev = Some(NodeRef::clone(parent));
+                }
+            }
+        }
+        Node::inner_insert(parent, node)?;
+        Node::notify(parent, |observer| observer.on_insert(parent, node));
+        Ok(())
+    }
+
+    /// Attach every subtree of `subtrees` under `parent`, in order, upgrading a [`Node::Leaf`] parent.
+    ///
+    /// All subtrees are checked before any of them is attached, so on error the tree is left untouched.
+    /// A subtree still linked beneath another node is detached from it first, as [`Node::split_off`]
+    /// would, and a subtree listed more than once is only attached at its first occurrence.
+    ///
+    /// ### Parameters
+    /// - `parent`: A refrence to the Node which receives the subtrees.
+    /// - `subtrees`: Roots of the subtrees to attach.
+    ///
+    /// ### Return
+    /// - Result of a empty tuple, or [`NodeError::CycleDetected`] if `parent` lies within one of the subtrees.
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent(1);
+    /// let subtrees = vec![Node::parent(2), Node::parent(3)];
+    /// Node::graft_many(&root, subtrees)?;
+    /// assert_eq!(root.borrow().children().len(), 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrument(level = "info", skip(subtrees))]
+    pub fn graft_many<I>(parent: &NodeRef<T>, subtrees: I) -> Result<(), NodeError>
+    where
+        I: IntoIterator<Item = NodeRef<T>>,
+    {
+        let mut unique: Vec<NodeRef<T>> = Vec::new();
+        for subtree in subtrees {
+            if !unique.iter().any(|seen| Rc::ptr_eq(seen, &subtree)) {
+                unique.push(subtree);
+            }
+        }
+        if unique.iter().any(|subtree| Node::is_within(subtree, parent)) {
+            return Err(NodeError::CycleDetected);
+        }
+        for subtree in &unique {
+            Node::detach(subtree)?;
+            Node::insert_node(parent, subtree)?;
+        }
+        Ok(())
+    }
+
+    fn inner_insert(parent: &NodeRef<T>, node: &NodeRef<T>) -> Result<(), NodeError> {
+        Node::forget_observer(node);
+        let mut p = parent.borrow_mut();
+        // Get mutable access to the parent
+        match &mut *p {
+            Node::Leaf { .. } => {
+                // If parent is a leaf, upgrade it to a parent and add this node as a child
+                p.upgrade_inner(vec![Rc::clone(node)])?;
+            }
+            Node::Parent { next, .. } => {
+                // If parent is already a parent, just add this node to its children
+                next.push(Rc::clone(node));
+            }
+        }
+
+        // Return the new child node
+        Ok(())
+    }
+
+    /// Like [`Node::inner_insert`] but places `node` at `index` among the existing children,
+    /// a leaf parent is upgraded with `node` as its only child.
+    /// Returns [`NodeError::IndexOutOfBounds`] if `index` exceeds the child count.
+    fn inner_insert_at(parent: &NodeRef<T>, node: &NodeRef<T>, index: usize) -> Result<(), NodeError> {
+        let mut p = parent.borrow_mut();
+        let len = p.degree();
+        if index > len {
+            return Err(NodeError::IndexOutOfBounds { index, len });
+        }
+        Node::forget_observer(node);
+        match &mut *p {
+            Node::Leaf { .. } => {
+                p.upgrade_inner(vec![Rc::clone(node)])?;
+            }
+            Node::Parent { next, .. } => {
+                next.insert(index, Rc::clone(node));
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes a child node from its parent [`Node::Parent`].
+    ///
+    /// ### Parameters
+    /// - `parent`: A refrence to the Node to which will add child to.
+    /// - `child`: A reference to the child node to be removed.
+    ///
+    /// ### Returns
     /// - A result of a [`bool`]: where `true` If the child was successfully removed.
     ///   and `false` If the child was not found among this node's children.
     ///
@@ -535,24 +1345,29 @@ where
     /// # Example
     ///
     /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
     /// let root = Node::parent(1);
     /// let child = Node::insert(&root, 2)?;
-    /// let grand_child = Node::insert(&child, 3);
+    /// let grand_child = Node::insert(&child, 3)?;
     /// let result = Node::pop(&child, &grand_child)?;
     /// assert!(result); // Successfully removed
+    /// # Ok(())
+    /// # }
     /// ```
     #[instrument(level = "trace")]
     pub fn pop(parent: &NodeRef<T>, child: &NodeRef<T>) -> Result<bool, NodeError>
     where
         T: Default + Clone + Copy,
     {
-        parent.borrow_mut().inner_pop(child)
+        let removed = parent.borrow_mut().inner_pop(child)?;
+        if removed {
+            Node::notify(parent, |observer| observer.on_remove(parent, child));
+        }
+        Ok(removed)
     }
 
-    fn inner_pop(&mut self, child: &NodeRef<T>) -> Result<bool, NodeError>
-    where
-        T: Default + Clone + Copy,
-    {
+    fn inner_pop(&mut self, child: &NodeRef<T>) -> Result<bool, NodeError> {
         match self {
             Self::Leaf { .. } => Err(NodeError::NotAParent),
             Self::Parent { next, prev, .. } => {
@@ -585,44 +1400,2510 @@ where
             }
         }
     }
-}
 
-impl<T> From<Node<T>> for NodeRef<T> {
-    fn from(node: Node<T>) -> Self {
-        Rc::new(RefCell::new(node))
+    /// Detaches `child` from `parent`, returning it as the root of a standalone subtree.
+    ///
+    /// The detached child has its parent refrence cleared, a [`Node::Leaf`] child being turned
+    /// into a childless [`Node::Parent`] so it is a root, and `parent` is **downgraded**
+    /// into a [`Node::Leaf`] when it is left without children, just like [`Node::pop`].
+    ///
+    /// ### Parameters
+    /// - `parent`: A refrence to the node currently holding `child`.
+    /// - `child`: A reference to the child node to be detached.
+    ///
+    /// ### Return
+    /// - Result of the detached `child`, [`NodeError::NotAParent`] if `parent` is a [`Node::Leaf`],
+    ///   or [`NodeError::ParentNodeNotFound`] if `child` does not belong to `parent`.
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent(1);
+    /// let branch = Node::insert(&root, 2)?;
+    /// let _ = Node::insert(&branch, 3)?;
+    ///
+    /// let tree = Node::split_off(&root, &branch)?;
+    /// assert!(tree.borrow().is_root());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrument(level = "trace")]
+    pub fn split_off(parent: &NodeRef<T>, child: &NodeRef<T>) -> Result<NodeRef<T>, NodeError> {
+        Node::unlink(parent, child)?;
+        let mut c = child.borrow_mut();
+        if let Self::Leaf { value, .. } = &mut *c {
+            let value = mem::take(value);
+            *c = Self::Parent {
+                value,
+                prev: None,
+                next: vec![],
+            };
+        }
+        drop(c);
+        Ok(Rc::clone(child))
     }
-}
 
-impl<T> Node<T> {
-    pub fn iter(node: NodeRef<T>) -> NodeIter<T> {
-        NodeIter::new(node)
+    /// Removes `child` from `parent` like [`Node::split_off`], but leaves a [`Node::Leaf`] child as is.
+    fn unlink(parent: &NodeRef<T>, child: &NodeRef<T>) -> Result<(), NodeError> {
+        if parent.borrow_mut().inner_pop(child)? {
+            Node::notify(parent, |observer| observer.on_remove(parent, child));
+            Ok(())
+        } else {
+            Err(NodeError::ParentNodeNotFound)
+        }
     }
-}
-
-pub struct NodeIter<T> {
-    queue: Vec<NodeRef<T>>,
-}
 
-impl<T> NodeIter<T> {
-    pub fn new(node: NodeRef<T>) -> NodeIter<T> {
-        let queue = vec![node];
-        NodeIter { queue }
+    /// Detaches `node` from its parent with [`Node::unlink`], if it has one,
+    /// so it can be linked beneath another node.
+    fn detach(node: &NodeRef<T>) -> Result<(), NodeError> {
+        let parent = node.borrow().parent_ref();
+        if let Some(parent) = parent {
+            Node::unlink(&parent, node)?;
+        }
+        Ok(())
     }
-}
-impl<T> Iterator for NodeIter<T> {
-    type Item = NodeRef<T>;
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if let Some(item) = self.queue.first().cloned() {
-            self.queue.remove(0); // Remove first element (FIFO)
+    /// Detaches the node found by following `path`, a list of child indices, down from `root`,
+    /// returning it as the root of its own tree. Its former parent is **downgraded** into a
+    /// [`Node::Leaf`] when it is left without children, as with [`Node::split_off`].
+    ///
+    /// ### Parameters
+    /// - `root`: A refrence to the node `path` starts from.
+    /// - `path`: Child indices leading to the node to remove.
+    ///
+    /// ### Return
+    /// - Result of the detached node, [`NodeError::CannotReplaceRoot`] for an empty `path`,
+    ///   or [`NodeError::IndexOutOfBounds`] if `path` does not lead to a node.
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent(1);
+    /// let _ = Node::insert(&root, 2)?;
+    ///
+    /// let removed = Node::remove_at_path(&root, &[0])?;
+    /// assert!(removed.borrow().is_root());
+    /// assert!(!root.borrow().has_children());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrument(level = "trace")]
+    pub fn remove_at_path(root: &NodeRef<T>, path: &[usize]) -> Result<NodeRef<T>, NodeError> {
+        if path.is_empty() {
+            return Err(NodeError::CannotReplaceRoot);
+        }
+        let node = Node::node_at_path(root, path)?;
+        let parent = node
+            .borrow()
+            .parent_ref()
+            .ok_or(NodeError::ParentNodeNotFound)?;
+        Node::split_off(&parent, &node)
+    }
 
-            if let Node::Parent { next, .. } = &*item.borrow() {
-                self.queue.extend(next.clone()); // Add children to queue
-            }
+    /// Removes every child of `parent` matching `pred`, returning the removed children in order.
+    ///
+    /// Removed children have their parent refrence cleared, and `parent` is **downgraded**
+    /// into a [`Node::Leaf`] when it is left without children.
+    ///
+    /// ### Parameters
+    /// - `parent`: A refrence to the node whose children are filtered.
+    /// - `pred`: Returns `true` for each child that should be removed.
+    ///
+    /// ### Return
+    /// - Result of the removed children, or [`NodeError::NotAParent`] if `parent` is a [`Node::Leaf`].
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent(1);
+    /// let _ = Node::insert(&root, 2)?;
+    /// let _ = Node::insert(&root, 3)?;
+    ///
+    /// let even = Node::drain_children_filter(&root, |c| c.borrow().value() % 2 == 0)?;
+    /// assert_eq!(even.len(), 1);
+    /// assert_eq!(root.borrow().children().len(), 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrument(level = "trace", skip(pred))]
+    pub fn drain_children_filter<F>(parent: &NodeRef<T>, pred: F) -> Result<Vec<NodeRef<T>>, NodeError>
+    where
+        F: Fn(&NodeRef<T>) -> bool,
+    {
+        // take the children out so `pred` is free to borrow the parent
+        let children = match &mut *parent.borrow_mut() {
+            Self::Parent { next, .. } => mem::take(next),
+            Self::Leaf { .. } => return Err(NodeError::NotAParent),
+        };
+        let (drained, kept): (Vec<_>, Vec<_>) = children.into_iter().partition(|c| pred(c));
+        for child in &drained {
+            child.borrow_mut().set_prev(None);
+        }
 
-            Some(item)
-        } else {
-            None
+        let mut p = parent.borrow_mut();
+        if let Self::Parent { next, .. } = &mut *p {
+            *next = kept;
+        }
+        if !drained.is_empty() && !p.has_children() && !p.is_root() {
+            p.downgrade_inner()?;
         }
+        drop(p);
+        for child in &drained {
+            Node::notify(parent, |observer| observer.on_remove(parent, child));
+        }
+        Ok(drained)
+    }
+
+    /// Removes every node deeper than `max_depth`, where the root is at depth `0`.
+    ///
+    /// Nodes at `max_depth` lose their children, which have their parent refrence cleared,
+    /// and are **downgraded** into a [`Node::Leaf`] unless they are the root.
+    ///
+    /// ### Return
+    /// - The number of nodes removed from the tree, descendants of removed children included.
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent(1);
+    /// let child = Node::insert(&root, 2)?;
+    /// let _ = Node::insert(&child, 3)?;
+    ///
+    /// assert_eq!(Node::truncate_depth(&root, 1), 1);
+    /// assert!(child.borrow().is_leaf());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrument(level = "trace")]
+    pub fn truncate_depth(root: &NodeRef<T>, max_depth: usize) -> usize {
+        let boundary: Vec<_> = Node::bfs_with_depth(root)
+            .take_while(|(depth, _)| *depth <= max_depth)
+            .filter(|(depth, node)| *depth == max_depth && node.borrow().has_children())
+            .map(|(_, node)| node)
+            .collect();
+
+        let mut removed = 0;
+        for node in boundary {
+            let mut n = node.borrow_mut();
+            let children = match &mut *n {
+                Self::Parent { next, .. } => mem::take(next),
+                Self::Leaf { .. } => Vec::new(),
+            };
+            for child in &children {
+                removed += Node::iter(Rc::clone(child)).count();
+                child.borrow_mut().set_prev(None);
+            }
+            if !n.is_root() {
+                // childless and not a root, so this cannot fail
+                let _ = n.downgrade_inner();
+            }
+            drop(n);
+            for child in &children {
+                Node::notify(&node, |observer| observer.on_remove(&node, child));
+            }
+        }
+        removed
+    }
+
+    /// Keeps only the nodes lying on a path from `root` to a node matching `pred`.
+    ///
+    /// A node is kept when its value matches or any of its descendants is kept, every other
+    /// subtree is detached. Kept nodes left without children are **downgraded** into a [`Node::Leaf`].
+    ///
+    /// ### Return
+    /// - `true` if `root` itself was kept, when `false` all of its children were removed.
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent(1);
+    /// let child = Node::insert(&root, 2)?;
+    /// let _ = Node::insert(&child, 3)?;
+    /// let _ = Node::insert(&root, 4)?;
+    ///
+    /// assert!(Node::retain_paths_to(&root, |value| *value == 3));
+    /// assert_eq!(Node::values_dfs(root).collect::<Vec<_>>(), vec![1, 2, 3]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrument(level = "trace", skip(pred))]
+    pub fn retain_paths_to<F>(root: &NodeRef<T>, pred: F) -> bool
+    where
+        F: Fn(&T) -> bool,
+    {
+        // post-order leaves each node's child results on top of the stack
+        let mut kept: Vec<bool> = Vec::new();
+        for node in Node::iter_post_order(Rc::clone(root)) {
+            let children = kept.split_off(kept.len() - node.borrow().degree());
+            Node::retain_children(&node, &children);
+            let keep = children.contains(&true) || pred(node.borrow().value());
+            kept.push(keep);
+        }
+        kept.pop().unwrap_or(false)
+    }
+
+    /// Keeps every branch holding at least one node whose value matches `pred`, any other
+    /// branch is detached. Unlike [`Node::retain_paths_to`], the whole subtree under a matching
+    /// node is kept, not only the path leading to it. Kept nodes left without children are
+    /// **downgraded** into a [`Node::Leaf`].
+    ///
+    /// ### Return
+    /// - `true` if `root` itself was kept, when `false` all of its children were removed.
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent(1);
+    /// let child = Node::insert(&root, 2)?;
+    /// let _ = Node::insert(&child, 3)?;
+    /// let _ = Node::insert(&root, 4)?;
+    ///
+    /// assert!(Node::keep_matching_branches(&root, |value| *value == 2));
+    /// assert_eq!(Node::values_dfs(root).collect::<Vec<_>>(), vec![1, 2, 3]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrument(level = "trace", skip(pred))]
+    pub fn keep_matching_branches<F>(root: &NodeRef<T>, pred: F) -> bool
+    where
+        F: Fn(&T) -> bool,
+    {
+        // first find out, bottom-up, which subtrees hold a match
+        let mut contains = BTreeMap::new();
+        for node in Node::iter_post_order(Rc::clone(root)) {
+            let n = node.borrow();
+            let found =
+                pred(n.value()) || n.children().iter().any(|child| contains[&Rc::as_ptr(child)]);
+            contains.insert(Rc::as_ptr(&node), found);
+        }
+
+        // then prune top-down, never descending into a matching node
+        let mut stack = vec![Rc::clone(root)];
+        while let Some(node) = stack.pop() {
+            if pred(node.borrow().value()) {
+                continue;
+            }
+            let keep: Vec<bool> = node
+                .borrow()
+                .children()
+                .iter()
+                .map(|child| contains[&Rc::as_ptr(child)])
+                .collect();
+            Node::retain_children(&node, &keep);
+            stack.extend(node.borrow().children().iter().cloned());
+        }
+        contains[&Rc::as_ptr(root)]
+    }
+
+    /// Rewrite the value of every node with `f`, a node for which `f` returns `None` is
+    /// detached along with its whole subtree, and `f` is never called on its descendants.
+    /// Kept nodes left without children are **downgraded** into a [`Node::Leaf`].
+    ///
+    /// ### Return
+    /// - `true` if `root` itself was kept, when `false` its value is left untouched and all
+    ///   of its children were removed.
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent(1);
+    /// let _ = Node::insert(&root, 2)?;
+    /// let _ = Node::insert(&root, 3)?;
+    ///
+    /// assert!(Node::retain_map(&root, |value| (*value != 2).then(|| value * 10)));
+    /// assert_eq!(Node::values_dfs(root).collect::<Vec<_>>(), vec![10, 30]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrument(level = "trace", skip(f))]
+    pub fn retain_map<F>(root: &NodeRef<T>, mut f: F) -> bool
+    where
+        F: FnMut(&T) -> Option<T>,
+    {
+        let Some(value) = f(root.borrow().value()) else {
+            let keep = vec![false; root.borrow().degree()];
+            Node::retain_children(root, &keep);
+            return false;
+        };
+        *root.borrow_mut().value_mut() = value;
+
+        let mut stack = vec![Rc::clone(root)];
+        while let Some(node) = stack.pop() {
+            let children = node.borrow().children().to_vec();
+            let mut keep = Vec::with_capacity(children.len());
+            for child in children {
+                let mapped = f(child.borrow().value());
+                keep.push(mapped.is_some());
+                if let Some(value) = mapped {
+                    *child.borrow_mut().value_mut() = value;
+                    stack.push(child);
+                }
+            }
+            Node::retain_children(&node, &keep);
+        }
+        true
+    }
+
+    /// Detaches every child whose entry in `keep` is `false`, downgrading `node`
+    /// when it is left without children and is not a root.
+    fn retain_children(node: &NodeRef<T>, keep: &[bool]) {
+        if !keep.contains(&false) {
+            return;
+        }
+        let mut removed = Vec::new();
+        let mut n = node.borrow_mut();
+        if let Self::Parent { next, .. } = &mut *n {
+            let mut keep = keep.iter();
+            next.retain(|child| {
+                let retain = keep.next().copied().unwrap_or(true);
+                if !retain {
+                    child.borrow_mut().set_prev(None);
+                    removed.push(Rc::clone(child));
+                }
+                retain
+            });
+        }
+        if !n.has_children() && !n.is_root() {
+            // childless and not a root, so this cannot fail
+            let _ = n.downgrade_inner();
+        }
+        drop(n);
+        for child in &removed {
+            Node::notify(node, |observer| observer.on_remove(node, child));
+        }
+    }
+
+    /// Re-hang the tree so that `node` becomes its root.
+    ///
+    /// Every link on the path from the old root down to `node` is reversed, each former
+    /// parent becoming the last child of the node that was below it. This mutates every
+    /// node along that path, the old root is **downgraded** into a [`Node::Leaf`] if it is
+    /// left without children, and an observer registered on the old root moves to `node`.
+    ///
+    /// ### Parameters
+    /// - `node`: A refrence to the node that becomes the root.
+    ///
+    /// ### Return
+    /// - Result of `node`, now the root, or [`NodeError`].
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent(1);
+    /// let child = Node::insert(&root, 2)?;
+    ///
+    /// let root = Node::reroot(&child)?;
+    /// assert!(root.borrow().is_root());
+    /// assert_eq!(*root.borrow().children()[0].borrow().value(), 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrument(level = "info")]
+    pub fn reroot(node: &NodeRef<T>) -> Result<NodeRef<T>, NodeError> {
+        let lineage = Node::lineage(node);
+        #[cfg(feature = "std")]
+        observer::transfer(&lineage[0], node);
+
+        for pair in lineage.windows(2) {
+            let (parent, child) = (&pair[0], &pair[1]);
+            {
+                let mut p = parent.borrow_mut();
+                if let Self::Parent { next, .. } = &mut *p {
+                    next.retain(|c| !Rc::ptr_eq(c, child));
+                }
+                p.set_prev(Some(child));
+                if !p.has_children() {
+                    p.downgrade_inner()?;
+                }
+            }
+            Node::inner_insert(child, parent)?;
+        }
+
+        node.borrow_mut().set_prev(None);
+        Ok(Rc::clone(node))
+    }
+
+    /// Collapses every run of single-child nodes, merging each only child into its parent with
+    /// `combine`, which receives the parent's value to update and the child's value.
+    /// The parent then takes over the children of the merged child.
+    ///
+    /// A non-root node left without children is **downgraded** into a [`Node::Leaf`].
+    ///
+    /// ### Return
+    /// - The number of nodes merged into their parent.
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent(1);
+    /// let child = Node::insert(&root, 2)?;
+    /// let _ = Node::insert(&child, 3)?;
+    ///
+    /// // keep the deepest value
+    /// assert_eq!(Node::collapse_chains(&root, |parent, child| *parent = *child), 2);
+    /// assert_eq!(*root.borrow().value(), 3);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrument(level = "trace", skip(combine))]
+    pub fn collapse_chains<F>(root: &NodeRef<T>, combine: F) -> usize
+    where
+        F: Fn(&mut T, &T),
+    {
+        let mut collapsed = 0;
+        let mut stack = vec![Rc::clone(root)];
+        while let Some(node) = stack.pop() {
+            let mut merged = false;
+            while node.borrow().degree() == 1 {
+                let child = Rc::clone(&node.borrow().children()[0]);
+                let grand_children = {
+                    let mut c = child.borrow_mut();
+                    c.set_prev(None);
+                    combine(node.borrow_mut().value_mut(), c.value());
+                    match &mut *c {
+                        Self::Parent { next, .. } => mem::take(next),
+                        Self::Leaf { .. } => Vec::new(),
+                    }
+                };
+                for grand_child in &grand_children {
+                    grand_child.borrow_mut().set_prev(Some(&node));
+                }
+                if let Self::Parent { next, .. } = &mut *node.borrow_mut() {
+                    next.clone_from(&grand_children);
+                }
+                Node::notify(&node, |observer| observer.on_remove(&node, &child));
+                for grand_child in &grand_children {
+                    Node::notify(&node, |observer| observer.on_insert(&node, grand_child));
+                }
+                collapsed += 1;
+                merged = true;
+            }
+
+            let mut n = node.borrow_mut();
+            if merged && !n.has_children() && !n.is_root() {
+                // `node` is a parent that just lost its last child, so this cannot fail
+                let _ = n.downgrade_inner();
+            }
+            stack.extend(n.children().iter().cloned());
+        }
+        collapsed
+    }
+
+    /// Removes every child whose value equals the value of an earlier sibling, keeping the first.
+    ///
+    /// Removed children are detached with their whole subtree, their children are **not**
+    /// merged into the surviving sibling.
+    ///
+    /// ### Parameters
+    /// - `parent`: A refrence to the Node whose children are deduplicated.
+    ///
+    /// ### Return
+    /// - Result of the number of children removed, or [`NodeError::NotAParent`] if `parent` is a [`Node::Leaf`].
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent(0);
+    /// let _ = Node::insert(&root, 1)?;
+    /// let _ = Node::insert(&root, 1)?;
+    /// assert_eq!(Node::dedup_children(&root)?, 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrument(level = "trace")]
+    pub fn dedup_children(parent: &NodeRef<T>) -> Result<usize, NodeError>
+    where
+        T: PartialEq,
+    {
+        let keep: Vec<bool> = {
+            let p = parent.borrow();
+            if p.is_leaf() {
+                return Err(NodeError::NotAParent);
+            }
+            let children = p.children();
+            children
+                .iter()
+                .enumerate()
+                .map(|(i, child)| {
+                    let value = child.borrow();
+                    !children[..i]
+                        .iter()
+                        .any(|earlier| earlier.borrow().value() == value.value())
+                })
+                .collect()
+        };
+        Node::retain_children(parent, &keep);
+        Ok(keep.iter().filter(|kept| !**kept).count())
+    }
+
+    /// Detaches every node without children at once, returning them in breadth first order.
+    ///
+    /// Parents left without children are **downgraded** into a [`Node::Leaf`], they are only
+    /// removed by the next call, so repeated calls peel the tree one layer at a time.
+    ///
+    /// ### Parameters
+    /// - `root`: A refrence to the node the walk starts from, it is never removed.
+    ///
+    /// ### Return
+    /// - The detached nodes, with their parent refrence cleared.
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent(1);
+    /// let branch = Node::insert(&root, 2)?;
+    /// let _ = Node::insert(&branch, 3)?;
+    /// let _ = Node::insert(&branch, 4)?;
+    /// let _ = Node::insert(&root, 5)?;
+    ///
+    /// assert_eq!(Node::peel_leaves(&root).len(), 3);
+    /// assert!(branch.borrow().is_leaf());
+    /// assert_eq!(Node::peel_leaves(&root).len(), 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrument(level = "trace")]
+    pub fn peel_leaves(root: &NodeRef<T>) -> Vec<NodeRef<T>> {
+        // decide on every node before mutating any, so new leaves wait for the next call
+        let parents: Vec<(NodeRef<T>, Vec<bool>)> = Node::iter(Rc::clone(root))
+            .filter(|node| node.borrow().has_children())
+            .map(|node| {
+                let keep = node
+                    .borrow()
+                    .children()
+                    .iter()
+                    .map(|child| child.borrow().has_children())
+                    .collect();
+                (node, keep)
+            })
+            .collect();
+
+        let mut peeled = Vec::new();
+        for (parent, keep) in &parents {
+            let p = parent.borrow();
+            let leaves = p.children().iter().zip(keep).filter(|(_, kept)| !**kept);
+            peeled.extend(leaves.map(|(leaf, _)| Rc::clone(leaf)));
+            drop(p);
+            Node::retain_children(parent, keep);
+        }
+        peeled
+    }
+
+    /// Downgrades every non-root [`Node::Parent`] without children into a [`Node::Leaf`].
+    ///
+    /// Subtrees that had their children moved elsewhere can be left as empty parents,
+    /// this walks the whole tree and collapses them back into leaves.
+    ///
+    /// ### Parameters
+    /// - `root`: A refrence to the node the walk starts from.
+    ///
+    /// ### Return
+    /// - The number of nodes that were downgraded.
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, NodeRef, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent(1);
+    /// let empty: NodeRef<u8> = Node::parent(2);
+    /// Node::insert_node(&root, &empty)?;
+    ///
+    /// assert_eq!(Node::trim_empty_parents(&root), 1);
+    /// assert!(empty.borrow().is_leaf());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrument(level = "trace")]
+    pub fn trim_empty_parents(root: &NodeRef<T>) -> usize {
+        let mut trimmed = 0;
+        for node in Node::iter(Rc::clone(root)) {
+            let mut n = node.borrow_mut();
+            // roots and leaves are rejected by `downgrade_inner`
+            if !n.has_children() && n.downgrade_inner().is_ok() {
+                trimmed += 1;
+            }
+        }
+        trimmed
+    }
+
+    /// Splices `node` out of the tree by moving its only child into `node`'s slot of its parent.
+    ///
+    /// `node` is left detached, with no parent and no children.
+    ///
+    /// ### Parameters
+    /// - `node`: A refrence to the node that will be replaced by its child.
+    ///
+    /// ### Return
+    /// - Result of the promoted child, [`NodeError::NotSingleChild`] if `node` does not have exactly one child,
+    ///   or [`NodeError::ParentNodeNotFound`] if `node` has no parent to promote into.
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, NodeRef, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent(1);
+    /// let child = Node::insert(&root, 2)?;
+    /// let grand_child = Node::insert(&child, 3)?;
+    ///
+    /// let promoted = Node::promote_only_child(&child)?;
+    /// assert!(NodeRef::ptr_eq(&promoted, &grand_child));
+    /// assert!(NodeRef::ptr_eq(&root.borrow().children()[0], &grand_child));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrument(level = "trace")]
+    pub fn promote_only_child(node: &NodeRef<T>) -> Result<NodeRef<T>, NodeError> {
+        if node.borrow().children().len() != 1 {
+            return Err(NodeError::NotSingleChild);
+        }
+        let parent = node
+            .borrow()
+            .parent_ref()
+            .ok_or(NodeError::ParentNodeNotFound)?;
+        let index = parent
+            .borrow()
+            .position_of(node)
+            .ok_or(NodeError::ParentNodeNotFound)?;
+
+        let child = {
+            let mut n = node.borrow_mut();
+            n.set_prev(None);
+            match &mut *n {
+                Self::Parent { next, .. } => next.remove(0),
+                Self::Leaf { .. } => unreachable!("checked for a single child"),
+            }
+        };
+        child.borrow_mut().set_prev(Some(&parent));
+        if let Self::Parent { next, .. } = &mut *parent.borrow_mut() {
+            next[index] = Rc::clone(&child);
+        }
+        Node::notify(&parent, |observer| observer.on_remove(&parent, node));
+        Node::notify(&parent, |observer| observer.on_insert(&parent, &child));
+        Ok(child)
+    }
+
+    /// Removes `node` from its parent, putting its children in its place, in order.
+    ///
+    /// The children now point at the former grand parent, and `node` is left detached without children.
+    /// If `node` was a [`Node::Leaf`] and the only child, a non-root parent is **downgraded** into a [`Node::Leaf`].
+    ///
+    /// ### Parameters
+    /// - `node`: A refrence to the node that is spliced out.
+    ///
+    /// ### Return
+    /// - Result of an empty tuple, or [`NodeError::CannotReplaceRoot`] if `node` is a root.
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent(1);
+    /// let child = Node::insert(&root, 2)?;
+    /// let _ = Node::insert(&child, 3)?;
+    ///
+    /// Node::splice_out(&child)?;
+    /// assert_eq!(Node::values_dfs(root).collect::<Vec<_>>(), vec![1, 3]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrument(level = "trace")]
+    pub fn splice_out(node: &NodeRef<T>) -> Result<(), NodeError> {
+        let parent = node
+            .borrow()
+            .parent_ref()
+            .ok_or(NodeError::CannotReplaceRoot)?;
+        let index = parent
+            .borrow()
+            .position_of(node)
+            .ok_or(NodeError::ParentNodeNotFound)?;
+
+        let children = {
+            let mut n = node.borrow_mut();
+            n.set_prev(None);
+            match &mut *n {
+                Self::Parent { next, .. } => mem::take(next),
+                Self::Leaf { .. } => Vec::new(),
+            }
+        };
+        for child in &children {
+            child.borrow_mut().set_prev(Some(&parent));
+        }
+
+        let mut p = parent.borrow_mut();
+        if let Self::Parent { next, .. } = &mut *p {
+            next.splice(index..=index, children.iter().cloned());
+        }
+        if !p.has_children() && !p.is_root() {
+            p.downgrade_inner()?;
+        }
+        drop(p);
+        Node::notify(&parent, |observer| observer.on_remove(&parent, node));
+        for child in &children {
+            Node::notify(&parent, |observer| observer.on_insert(&parent, child));
+        }
+        Ok(())
+    }
+}
+
+impl<T> From<Node<T>> for NodeRef<T> {
+    fn from(node: Node<T>) -> Self {
+        Rc::new(RefCell::new(node))
+    }
+}
+
+impl<T> Node<T> {
+    pub fn iter(node: NodeRef<T>) -> NodeIter<T> {
+        NodeIter::new(node)
+    }
+
+    /// Create a [`WeakNodeRef`] to `node`.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn downgrade_ref(node: &NodeRef<T>) -> WeakNodeRef<T> {
+        Rc::downgrade(node)
+    }
+
+    /// Get back a [`NodeRef`] from a [`WeakNodeRef`], or `None` if the node was dropped.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn upgrade_ref(weak: &WeakNodeRef<T>) -> Option<NodeRef<T>> {
+        weak.upgrade()
+    }
+
+    /// Iterate through the tree breadth first, pairing each node with its index among its siblings.
+    pub fn iter_with_sibling_index(root: NodeRef<T>) -> SiblingIndexIter<T> {
+        SiblingIndexIter::new(root)
+    }
+
+    /// Iterate over every parent-child link of the tree in breadth first order.
+    pub fn edges(root: NodeRef<T>) -> EdgeIter<T> {
+        EdgeIter::new(root)
+    }
+
+    /// Iterate through the tree depth first, visiting each node before its children (pre-order).
+    pub fn iter_dfs(node: NodeRef<T>) -> DfsIter<T> {
+        DfsIter::new(node)
+    }
+
+    /// Iterate through the tree depth first, visiting each node after its children (post-order).
+    pub fn iter_post_order(node: NodeRef<T>) -> PostOrderIter<T> {
+        PostOrderIter::new(node)
+    }
+
+    /// Collect every node of the tree into a flat list, in the requested [`TraversalOrder`].
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, TraversalOrder, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent(1);
+    /// let child = Node::insert(&root, 2)?;
+    /// let _ = Node::insert(&child, 3)?;
+    ///
+    /// let nodes = Node::flatten(&root, TraversalOrder::PostOrder);
+    /// assert_eq!(*nodes[0].borrow().value(), 3);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn flatten(root: &NodeRef<T>, order: TraversalOrder) -> Vec<NodeRef<T>> {
+        let root = Rc::clone(root);
+        match order {
+            TraversalOrder::Bfs => Node::iter(root).collect(),
+            TraversalOrder::PreOrder => Node::iter_dfs(root).collect(),
+            TraversalOrder::PostOrder => Node::iter_post_order(root).collect(),
+        }
+    }
+
+    /// Deep-clone the values and shape of the tree into an owned [`TreeSnapshot`],
+    /// which can later be turned back into a tree with [`Node::restore`].
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent(1);
+    /// let snapshot = Node::snapshot(&root);
+    /// let _ = Node::insert(&root, 2)?;
+    ///
+    /// let undone = Node::restore(&snapshot);
+    /// assert!(!undone.borrow().has_children());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn snapshot(root: &NodeRef<T>) -> TreeSnapshot<T>
+    where
+        T: Clone,
+    {
+        let nodes = Node::iter_dfs(Rc::clone(root))
+            .map(|node| {
+                let n = node.borrow();
+                (n.value().clone(), n.degree(), !n.is_leaf())
+            })
+            .collect();
+        TreeSnapshot { nodes }
+    }
+
+    /// Rebuild a fresh tree from a [`TreeSnapshot`], returning its root.
+    ///
+    /// Every node is restored as the same variant it was snapshot as, so a [`Node::Parent`]
+    /// left without children stays one. The root is always restored as a [`Node::Parent`].
+    ///
+    /// A [`TreeSnapshot`] can only be taken from a whole tree with [`Node::snapshot`],
+    /// so it always describes exactly one tree and restoring it cannot fail.
+    pub fn restore(snapshot: &TreeSnapshot<T>) -> NodeRef<T>
+    where
+        T: Clone,
+    {
+        let mut nodes = snapshot.nodes.iter().cloned();
+        Node::from_pre_order_variants(|| nodes.next().ok_or(()))
+            .expect("snapshot holds a complete tree")
+    }
+
+    /// Wrap a clone of the [`Node`] behind `node` in a new [`NodeRef`], this is what the derived
+    /// [`Clone`] does. The value is cloned, but the children are the **same** `Rc`s as the original's,
+    /// and they keep pointing at the original as their parent. The clone also keeps the original's parent
+    /// refrence, without being one of that parent's children.
+    ///
+    /// Use [`Node::deep_clone`] for an independent copy of the whole subtree.
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, NodeRef, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent(1);
+    /// let child = Node::insert(&root, 2)?;
+    ///
+    /// let shallow = Node::clone_shallow(&root);
+    /// assert!(NodeRef::ptr_eq(&shallow.borrow().children()[0], &child));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn clone_shallow(node: &NodeRef<T>) -> NodeRef<T>
+    where
+        T: Clone,
+    {
+        node.borrow().clone().into()
+    }
+
+    /// Copy the subtree under `node` into a brand new tree, cloning every value and
+    /// allocating every node anew. The copy of `node` is returned as a detached root,
+    /// and changes to either tree never show up in the other.
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, NodeRef, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent(1);
+    /// let child = Node::insert(&root, 2)?;
+    ///
+    /// let deep = Node::deep_clone(&root);
+    /// assert!(!NodeRef::ptr_eq(&deep.borrow().children()[0], &child));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn deep_clone(node: &NodeRef<T>) -> NodeRef<T>
+    where
+        T: Clone,
+    {
+        Node::copy_tree(node, |node| node.borrow().value().clone(), false)
+    }
+
+    /// Copy the branch under `node` into a standalone tree, as [`Node::deep_clone`] does,
+    /// making sure the copy of `node` is a root even when `node` is a [`Node::Leaf`].
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent(1);
+    /// let child = Node::insert(&root, 2)?;
+    ///
+    /// let copy = Node::clone_as_root(&child);
+    /// assert!(copy.borrow().is_root());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn clone_as_root(node: &NodeRef<T>) -> NodeRef<T>
+    where
+        T: Clone,
+    {
+        if node.borrow().is_leaf() {
+            return Node::Parent {
+                value: node.borrow().value().clone(),
+                prev: None,
+                next: vec![],
+            }
+            .into();
+        }
+        Node::deep_clone(node)
+    }
+
+    /// Rebuild a tree from its values and [`Node::degree_sequence`], both in breadth first order.
+    ///
+    /// Nodes with a degree of `0` become a [`Node::Leaf`], except for the root.
+    ///
+    /// ### Return
+    /// - Result of the new root, or [`NodeError::MalformedSequence`] if the sequences are empty,
+    ///   differ in length, or their degrees do not add up to exactly one tree.
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::from_sequences(&[1, 2, 3], &[2, 0, 0])?;
+    /// assert_eq!(root.borrow().children().len(), 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_sequences(values: &[T], degrees: &[usize]) -> Result<NodeRef<T>, NodeError>
+    where
+        T: Clone,
+    {
+        if values.is_empty() || values.len() != degrees.len() {
+            return Err(NodeError::MalformedSequence);
+        }
+        // the children of each node come right after those of the nodes before it,
+        // so every node must already be reached by the time its own children are counted
+        let mut reached = 1;
+        for (i, &degree) in degrees.iter().enumerate() {
+            if i >= reached {
+                return Err(NodeError::MalformedSequence);
+            }
+            reached = reached.checked_add(degree).ok_or(NodeError::MalformedSequence)?;
+        }
+        if reached != values.len() {
+            return Err(NodeError::MalformedSequence);
+        }
+
+        let nodes: Vec<NodeRef<T>> = values
+            .iter()
+            .zip(degrees)
+            .enumerate()
+            .map(|(i, (value, &degree))| {
+                if i == 0 || degree > 0 {
+                    Node::Parent {
+                        value: value.clone(),
+                        prev: None,
+                        next: Vec::with_capacity(degree),
+                    }
+                    .into()
+                } else {
+                    Node::Leaf {
+                        value: value.clone(),
+                        prev: None,
+                    }
+                    .into()
+                }
+            })
+            .collect();
+
+        let mut first = 1;
+        for (parent, &degree) in nodes.iter().zip(degrees) {
+            let children = &nodes[first..first + degree];
+            for child in children {
+                child.borrow_mut().set_prev(Some(parent));
+            }
+            if let Self::Parent { next, .. } = &mut *parent.borrow_mut() {
+                next.extend(children.iter().cloned());
+            }
+            first += degree;
+        }
+        Ok(Rc::clone(&nodes[0]))
+    }
+
+    /// Arrange `values`, taken in breadth first order, into a complete `k`-ary tree where
+    /// every level but the last is full and the last is filled from the left.
+    ///
+    /// ### Return
+    /// - The new root, or `None` if `values` is empty or `k` is `0`.
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::Node;
+    /// let root = Node::build_balanced(&[1, 2, 3, 4], 2).unwrap();
+    /// assert_eq!(root.borrow().children().len(), 2);
+    /// assert_eq!(root.borrow().children()[0].borrow().children().len(), 1);
+    /// ```
+    pub fn build_balanced(values: &[T], k: usize) -> Option<NodeRef<T>>
+    where
+        T: Clone,
+    {
+        if k == 0 {
+            return None;
+        }
+        // the children of node `i` are the values at `k * i + 1 ..= k * i + k`,
+        // a first child past `usize::MAX` being past the end of `values` as well
+        let len = values.len();
+        let degrees: Vec<usize> = (0..len)
+            .map(|i| {
+                k.checked_mul(i)
+                    .and_then(|first| first.checked_add(1))
+                    .map_or(0, |first| len.saturating_sub(first).min(k))
+            })
+            .collect();
+        Node::from_sequences(values, &degrees).ok()
+    }
+
+    /// Build a new tree with the same shape as `root`, the value of each new node being
+    /// computed by `f` from the matching source node. `f` receives the whole [`NodeRef`],
+    /// so it may look at the node's children or parent and not only at its value.
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent('a');
+    /// let _ = Node::insert(&root, 'b')?;
+    ///
+    /// let degrees = Node::structure_map(&root, |node| node.borrow().degree());
+    /// assert_eq!(*degrees.borrow().value(), 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn structure_map<U, F>(root: &NodeRef<T>, f: F) -> NodeRef<U>
+    where
+        F: FnMut(&NodeRef<T>) -> U,
+    {
+        Node::copy_tree(root, f, false)
+    }
+
+    /// Deep-clone the tree with every node's children in reverse order, as if seen in a mirror.
+    /// The original tree is left untouched.
+    pub fn mirror(root: &NodeRef<T>) -> NodeRef<T>
+    where
+        T: Clone,
+    {
+        Node::copy_tree(root, |node| node.borrow().value().clone(), true)
+    }
+
+    /// Clone the value of `node`, holding the borrow only for the duration of the call.
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::Node;
+    /// let root = Node::parent(1);
+    /// assert_eq!(Node::read_value(&root) + 1, 2);
+    /// ```
+    #[inline]
+    pub fn read_value(node: &NodeRef<T>) -> T
+    where
+        T: Clone,
+    {
+        node.borrow().value().clone()
+    }
+
+    /// Clone the list of children of `node` without risking a borrow panic.
+    ///
+    /// ### Return
+    /// - Result of the children, empty for a [`Node::Leaf`], or [`NodeError::AlreadyBorrowed`]
+    ///   if `node` is currently borrowed mutably.
+    pub fn children_snapshot(node: &NodeRef<T>) -> Result<Vec<NodeRef<T>>, NodeError> {
+        let n = node.try_borrow()?;
+        Ok(n.children().to_vec())
+    }
+
+    /// Iterate over clones of the children of `parent`, taken up front so that no borrow of
+    /// `parent` is held during the loop and each child may be mutated freely.
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent(1);
+    /// let _ = Node::insert(&root, 2)?;
+    ///
+    /// for child in Node::child_refs(&root) {
+    ///     *child.borrow_mut().value_mut() += 1;
+    /// }
+    /// assert_eq!(*root.borrow().children()[0].borrow().value(), 3);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn child_refs(parent: &NodeRef<T>) -> vec::IntoIter<NodeRef<T>> {
+        parent.borrow().children().to_vec().into_iter()
+    }
+
+    /// Fold every value of the tree in breadth first order with `f`, starting from a clone
+    /// of the root's value instead of an explicit accumulator.
+    ///
+    /// ### Return
+    /// - The reduced value, a tree always holds its root so this is always `Some`.
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent(1);
+    /// let _ = Node::insert(&root, 2)?;
+    /// assert_eq!(Node::reduce_values(&root, |sum, value| sum + value), Some(3));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn reduce_values<F>(root: &NodeRef<T>, f: F) -> Option<T>
+    where
+        T: Clone,
+        F: Fn(T, &T) -> T,
+    {
+        let mut nodes = Node::iter(Rc::clone(root));
+        let first = nodes.next()?.borrow().value().clone();
+        Some(nodes.fold(first, |acc, node| f(acc, node.borrow().value())))
+    }
+
+    /// Walk the tree depth first, calling `enter` with each node and its depth before its
+    /// children are visited, and `leave` once all of them were.
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent(1);
+    /// let _ = Node::insert(&root, 2)?;
+    ///
+    /// let tags = std::cell::RefCell::new(String::new());
+    /// Node::walk(
+    ///     &root,
+    ///     |node, _| tags.borrow_mut().push_str(&format!("<{}>", node.borrow().value())),
+    ///     |node, _| tags.borrow_mut().push_str(&format!("</{}>", node.borrow().value())),
+    /// );
+    /// assert_eq!(tags.into_inner(), "<1><2></2></1>");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn walk<F, G>(root: &NodeRef<T>, mut enter: F, mut leave: G)
+    where
+        F: FnMut(&NodeRef<T>, usize),
+        G: FnMut(&NodeRef<T>, usize),
+    {
+        // each node is pushed twice, first to enter it then to leave it
+        let mut stack = vec![(Rc::clone(root), 0, false)];
+        while let Some((node, depth, entered)) = stack.pop() {
+            if entered {
+                leave(&node, depth);
+                continue;
+            }
+            enter(&node, depth);
+            stack.push((Rc::clone(&node), depth, true));
+            stack.extend(
+                node.borrow()
+                    .children()
+                    .iter()
+                    .rev()
+                    .map(|child| (Rc::clone(child), depth + 1, false)),
+            );
+        }
+    }
+
+    /// Aggregate the tree bottom-up, calling `f` on each node's value together with the
+    /// results already computed for its children, in order.
+    ///
+    /// ### Return
+    /// - The result computed for `root`.
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent(1);
+    /// let child = Node::insert(&root, 2)?;
+    /// let _ = Node::insert(&child, 3)?;
+    ///
+    /// let height = Node::fold_up(&root, |_, children: &[usize]| {
+    ///     children.iter().max().map_or(0, |h| h + 1)
+    /// });
+    /// assert_eq!(height, 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn fold_up<A, F>(root: &NodeRef<T>, f: F) -> A
+    where
+        F: Fn(&T, &[A]) -> A,
+    {
+        // post-order leaves each node's child results on top of the stack
+        let mut results: Vec<A> = Vec::new();
+        for node in Node::iter_post_order(Rc::clone(root)) {
+            let n = node.borrow();
+            let children = results.split_off(results.len() - n.degree());
+            results.push(f(n.value(), &children));
+        }
+        results.pop().expect("post-order always yields the root")
+    }
+
+    /// Compare the values of the tree, in breadth first order, against `expected`.
+    ///
+    /// ### Return
+    /// - `true` if both sequences hold the same values and have the same length.
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent(1);
+    /// let _ = Node::insert(&root, 2)?;
+    /// assert!(Node::values_equal(&root, &[1, 2]));
+    /// assert!(!Node::values_equal(&root, &[1]));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn values_equal(root: &NodeRef<T>, expected: &[T]) -> bool
+    where
+        T: PartialEq,
+    {
+        let mut expected = expected.iter();
+        Node::iter(Rc::clone(root)).all(|node| expected.next() == Some(node.borrow().value()))
+            && expected.next().is_none()
+    }
+
+    /// Clone the values of the children of `parent`, in order.
+    ///
+    /// ### Return
+    /// - The child values, empty for a [`Node::Leaf`].
+    pub fn child_values(parent: &NodeRef<T>) -> Vec<T>
+    where
+        T: Clone,
+    {
+        parent
+            .borrow()
+            .children()
+            .iter()
+            .map(|child| child.borrow().value().clone())
+            .collect()
+    }
+
+    /// Every other child of `node`'s parent, in order.
+    ///
+    /// ### Return
+    /// - The siblings of `node`, empty for a root or an only child.
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent(1);
+    /// let child = Node::insert(&root, 2)?;
+    /// let _ = Node::insert(&root, 3)?;
+    /// assert_eq!(*Node::siblings(&child)[0].borrow().value(), 3);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn siblings(node: &NodeRef<T>) -> Vec<NodeRef<T>> {
+        let Some(parent) = node.borrow().parent_ref() else {
+            return Vec::new();
+        };
+        let p = parent.borrow();
+        p.children()
+            .iter()
+            .filter(|sibling| !Rc::ptr_eq(sibling, node))
+            .cloned()
+            .collect()
+    }
+
+    /// Render the tree one node per line in depth first pre-order, each value formatted
+    /// with [`Debug`] and prefixed by `indent` repeated once per level of depth.
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent(1);
+    /// let child = Node::insert(&root, 2)?;
+    /// let _ = Node::insert(&child, 3)?;
+    /// assert_eq!(Node::to_indented(&root, "  "), "1\n  2\n    3\n");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_indented(root: &NodeRef<T>, indent: &str) -> String
+    where
+        T: Debug,
+    {
+        use core::fmt::Write;
+
+        let mut out = String::new();
+        let mut stack = vec![(0, Rc::clone(root))];
+        while let Some((depth, node)) = stack.pop() {
+            let n = node.borrow();
+            for _ in 0..depth {
+                out.push_str(indent);
+            }
+            // writing into a `String` never fails
+            let _ = writeln!(out, "{:?}", n.value());
+            stack.extend(n.children().iter().rev().map(|child| (depth + 1, Rc::clone(child))));
+        }
+        out
+    }
+
+    /// Iterate over cloned values of the tree in depth first pre-order.
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent(1);
+    /// let child = Node::insert(&root, 2)?;
+    /// let _ = Node::insert(&child, 3)?;
+    /// let _ = Node::insert(&root, 4)?;
+    /// assert_eq!(Node::values_dfs(root).collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn values_dfs(root: NodeRef<T>) -> impl Iterator<Item = T>
+    where
+        T: Clone,
+    {
+        Node::iter_dfs(root).map(|node| node.borrow().value().clone())
+    }
+
+    /// Iterate over cloned values of the tree in depth first post-order, the root's value coming last.
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent(1);
+    /// let child = Node::insert(&root, 2)?;
+    /// let _ = Node::insert(&child, 3)?;
+    /// let _ = Node::insert(&root, 4)?;
+    /// assert_eq!(Node::values_post_order(root).collect::<Vec<_>>(), vec![3, 2, 4, 1]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn values_post_order(root: NodeRef<T>) -> impl Iterator<Item = T>
+    where
+        T: Clone,
+    {
+        Node::iter_post_order(root).map(|node| node.borrow().value().clone())
+    }
+
+    /// Lazily map every value of the tree with `f`, in breadth first order.
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent(1);
+    /// let _ = Node::insert(&root, 2)?;
+    /// let doubled: Vec<_> = Node::iter_map(root, |value| value * 2).collect();
+    /// assert_eq!(doubled, vec![2, 4]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn iter_map<U, F>(root: NodeRef<T>, mut f: F) -> impl Iterator<Item = U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        Node::iter(root).map(move |node| f(node.borrow().value()))
+    }
+
+    /// Split the children of `parent` at `index` without mutating the tree.
+    ///
+    /// ### Return
+    /// - Result of the children before `index` and those from `index` onward,
+    ///   or [`NodeError::IndexOutOfBounds`] if `index` is past the number of children.
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent(1);
+    /// let _ = Node::insert(&root, 2)?;
+    /// let _ = Node::insert(&root, 3)?;
+    ///
+    /// let (left, right) = Node::partition_children(&root, 1)?;
+    /// assert_eq!((left.len(), right.len()), (1, 1));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[allow(clippy::type_complexity)]
+    pub fn partition_children(
+        parent: &NodeRef<T>,
+        index: usize,
+    ) -> Result<(Vec<NodeRef<T>>, Vec<NodeRef<T>>), NodeError> {
+        let p = parent.borrow();
+        let children = p.children();
+        if index > children.len() {
+            return Err(NodeError::IndexOutOfBounds { index, len: children.len() });
+        }
+        let (left, right) = children.split_at(index);
+        Ok((left.to_vec(), right.to_vec()))
+    }
+
+    /// Clone the values of every [`Node::Leaf`] in the tree, in depth first pre-order,
+    /// i.e. left to right.
+    pub fn leaf_values(root: &NodeRef<T>) -> Vec<T>
+    where
+        T: Clone,
+    {
+        Node::iter_dfs(Rc::clone(root))
+            .filter(|node| node.borrow().is_leaf())
+            .map(|node| node.borrow().value().clone())
+            .collect()
+    }
+
+    /// Iterate over the tree in breadth first order, pairing each node with its parent,
+    /// `None` for `root`. Parents are tracked while walking rather than read back from each node.
+    pub fn iter_with_parent(root: NodeRef<T>) -> impl Iterator<Item = (Option<NodeRef<T>>, NodeRef<T>)> {
+        let mut queue = VecDeque::from([(None, root)]);
+        core::iter::from_fn(move || {
+            let (parent, node) = queue.pop_front()?;
+            queue.extend(
+                node.borrow()
+                    .children()
+                    .iter()
+                    .map(|child| (Some(Rc::clone(&node)), Rc::clone(child))),
+            );
+            Some((parent, node))
+        })
+    }
+
+    /// Iterate over the tree in reverse breadth first order, the deepest level first and
+    /// right to left within each level, the root coming last.
+    ///
+    /// The whole breadth first order is buffered before the first node is yielded.
+    pub fn iter_rev_bfs(root: NodeRef<T>) -> impl Iterator<Item = NodeRef<T>> {
+        Node::iter(root).collect::<Vec<_>>().into_iter().rev()
+    }
+
+    /// Iterate over the tree in breadth first order, never descending past `max_depth`,
+    /// where the root is at depth `0`.
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent(1);
+    /// let child = Node::insert(&root, 2)?;
+    /// let _ = Node::insert(&child, 3)?;
+    /// assert_eq!(Node::iter_to_depth(root, 1).count(), 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn iter_to_depth(root: NodeRef<T>, max_depth: usize) -> impl Iterator<Item = NodeRef<T>> {
+        let mut queue = VecDeque::from([(0, root)]);
+        core::iter::from_fn(move || {
+            let (depth, node) = queue.pop_front()?;
+            if depth < max_depth {
+                queue.extend(
+                    node.borrow()
+                        .children()
+                        .iter()
+                        .map(|child| (depth + 1, Rc::clone(child))),
+                );
+            }
+            Some(node)
+        })
+    }
+
+    /// Rough number of bytes used by the tree, the size of every [`Node`] plus the
+    /// capacity of every child list.
+    ///
+    /// This is an approximation, it ignores the reference counts and [`RefCell`] flag around
+    /// each node, allocator overhead, and anything owned by the values themselves.
+    pub fn estimate_bytes(root: &NodeRef<T>) -> usize {
+        Node::iter(Rc::clone(root))
+            .map(|node| {
+                let children = match &*node.borrow() {
+                    Self::Parent { next, .. } => next.capacity(),
+                    Self::Leaf { .. } => 0,
+                };
+                mem::size_of::<Node<T>>() + children * mem::size_of::<NodeRef<T>>()
+            })
+            .sum()
+    }
+
+    /// Count the nodes that sit exactly at `depth`, where the root is at depth `0`.
+    ///
+    /// The walk goes level by level and stops once `depth` is reached,
+    /// nodes below it are never visited.
+    pub fn count_at_depth(root: &NodeRef<T>, depth: usize) -> usize {
+        let mut level = vec![Rc::clone(root)];
+        for _ in 0..depth {
+            level = level
+                .iter()
+                .flat_map(|node| node.borrow().children().to_vec())
+                .collect();
+            if level.is_empty() {
+                break;
+            }
+        }
+        level.len()
+    }
+
+    /// ### Return
+    /// - The largest [`Node::degree`] of any node in the tree.
+    pub fn max_degree(root: &NodeRef<T>) -> usize {
+        Node::iter(Rc::clone(root))
+            .map(|node| node.borrow().degree())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// The [`Node::degree`] of every node in breadth first order, together with the values
+    /// in the same order this fully describes the tree, see [`Node::from_sequences`].
+    pub fn degree_sequence(root: &NodeRef<T>) -> Vec<usize> {
+        Node::iter(Rc::clone(root))
+            .map(|node| node.borrow().degree())
+            .collect()
+    }
+
+    /// The nodes of the deepest path from `root` down to a node without children,
+    /// the leftmost one winning ties.
+    ///
+    /// ### Return
+    /// - The path, starting with `root`, whose length is the height of the tree plus one.
+    pub fn longest_path(root: &NodeRef<T>) -> Vec<NodeRef<T>> {
+        let mut deepest = (0, Rc::clone(root));
+        let mut stack = vec![(0, Rc::clone(root))];
+        while let Some((depth, node)) = stack.pop() {
+            if depth > deepest.0 {
+                deepest = (depth, Rc::clone(&node));
+            }
+            stack.extend(
+                node.borrow()
+                    .children()
+                    .iter()
+                    .rev()
+                    .map(|child| (depth + 1, Rc::clone(child))),
+            );
+        }
+        let mut path = Node::lineage(&deepest.1);
+        // lineage climbs to the topmost ancestor, `root` may sit below it
+        let start = path.iter().position(|node| Rc::ptr_eq(node, root)).unwrap_or(0);
+        path.drain(..start);
+        path
+    }
+
+    /// Count the parent to child links of the tree for which `pred` holds, called with
+    /// the parent's value first and the child's second.
+    pub fn count_edges_where<F>(root: &NodeRef<T>, pred: F) -> usize
+    where
+        F: Fn(&T, &T) -> bool,
+    {
+        Node::edges(Rc::clone(root))
+            .filter(|(parent, child)| pred(parent.borrow().value(), child.borrow().value()))
+            .count()
+    }
+
+    /// Check if every node with children holds exactly `k` of them.
+    pub fn is_full(root: &NodeRef<T>, k: usize) -> bool {
+        Node::iter(Rc::clone(root)).all(|node| {
+            let degree = node.borrow().degree();
+            degree == 0 || degree == k
+        })
+    }
+
+    /// Check if the tree is full (see [`Node::is_full`]) and every node without children
+    /// sits at the same depth.
+    pub fn is_perfect(root: &NodeRef<T>, k: usize) -> bool {
+        let mut leaf_depth = None;
+        let mut stack = vec![(Rc::clone(root), 0)];
+        while let Some((node, depth)) = stack.pop() {
+            let n = node.borrow();
+            match n.degree() {
+                0 => {
+                    if *leaf_depth.get_or_insert(depth) != depth {
+                        return false;
+                    }
+                }
+                degree if degree == k => {
+                    stack.extend(n.children().iter().map(|child| (Rc::clone(child), depth + 1)));
+                }
+                _ => return false,
+            }
+        }
+        true
+    }
+
+    /// Breadth first walk pairing each node with its depth, the root being at depth `0`.
+    fn bfs_with_depth(root: &NodeRef<T>) -> impl Iterator<Item = (usize, NodeRef<T>)> + use<T> {
+        let mut queue = VecDeque::from([(0, Rc::clone(root))]);
+        core::iter::from_fn(move || {
+            let (depth, node) = queue.pop_front()?;
+            queue.extend(
+                node.borrow()
+                    .children()
+                    .iter()
+                    .map(|child| (depth + 1, Rc::clone(child))),
+            );
+            Some((depth, node))
+        })
+    }
+
+    /// Call `f` once per depth, from the root down, with the nodes sitting at that depth
+    /// in breadth first order.
+    ///
+    /// Every level is collected before `f` is first called, so no node is borrowed while
+    /// `f` runs and it is free to mutate them.
+    pub fn for_each_level_mut<F>(root: &NodeRef<T>, mut f: F)
+    where
+        F: FnMut(usize, &[NodeRef<T>]),
+    {
+        for (depth, level) in Node::nodes_by_depth(root) {
+            f(depth, &level);
+        }
+    }
+
+    /// Search the tree under `root` for `target`, comparing by pointer, and return its depth
+    /// relative to `root`. Only child links are followed, parent refrences are never read.
+    ///
+    /// ### Return
+    /// - The depth of `target`, `0` for `root` itself, or `None` if it is not in the tree.
+    pub fn depth_from_root(root: &NodeRef<T>, target: &NodeRef<T>) -> Option<usize> {
+        Node::bfs_with_depth(root)
+            .find(|(_, node)| Rc::ptr_eq(node, target))
+            .map(|(depth, _)| depth)
+    }
+
+    /// Group every node of the tree by its depth, keeping breadth first order within each depth.
+    pub fn nodes_by_depth(root: &NodeRef<T>) -> BTreeMap<usize, Vec<NodeRef<T>>> {
+        let mut levels: BTreeMap<usize, Vec<NodeRef<T>>> = BTreeMap::new();
+        for (depth, node) in Node::bfs_with_depth(root) {
+            levels.entry(depth).or_default().push(node);
+        }
+        levels
+    }
+
+    /// Collect every node of the tree in post-order, each paired with its depth, the root
+    /// being last at depth `0`.
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent(1);
+    /// let _ = Node::insert(&root, 2)?;
+    ///
+    /// let order = Node::post_order_with_depth(&root);
+    /// assert_eq!(order.iter().map(|(depth, _)| *depth).collect::<Vec<_>>(), vec![1, 0]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn post_order_with_depth(root: &NodeRef<T>) -> Vec<(usize, NodeRef<T>)> {
+        let mut order = Vec::new();
+        let mut stack = vec![(0, Rc::clone(root), false)];
+        while let Some((depth, node, expanded)) = stack.pop() {
+            if expanded || !node.borrow().has_children() {
+                order.push((depth, node));
+                continue;
+            }
+            let children: Vec<_> = node.borrow().children().iter().rev().cloned().collect();
+            stack.push((depth, node, true));
+            stack.extend(children.into_iter().map(|child| (depth + 1, child, false)));
+        }
+        order
+    }
+
+    /// Convert the tree into a [`petgraph`] directed graph, with an edge from each parent to each of its children.
+    ///
+    /// Graph nodes are added in breadth first order, so the root is always at index `0`.
+    #[cfg(feature = "petgraph")]
+    pub fn to_petgraph(root: &NodeRef<T>) -> petgraph::graph::DiGraph<T, ()>
+    where
+        T: Clone,
+    {
+        let mut graph = petgraph::graph::DiGraph::new();
+        let mut queue = VecDeque::from([(Rc::clone(root), None)]);
+        while let Some((node, parent)) = queue.pop_front() {
+            let n = node.borrow();
+            let index = graph.add_node(n.value().clone());
+            if let Some(parent) = parent {
+                graph.add_edge(parent, index, ());
+            }
+            queue.extend(n.children().iter().map(|child| (Rc::clone(child), Some(index))));
+        }
+        graph
+    }
+
+    /// Every node whose value lies within `lo..=hi`, in breadth first order.
+    pub fn values_in_range(root: &NodeRef<T>, lo: &T, hi: &T) -> Vec<NodeRef<T>>
+    where
+        T: PartialOrd,
+    {
+        Node::iter(Rc::clone(root))
+            .filter(|node| {
+                let n = node.borrow();
+                lo <= n.value() && n.value() <= hi
+            })
+            .collect()
+    }
+
+    /// Find the node holding the smallest value, ties going to the first one in breadth first order.
+    pub fn min_value(root: &NodeRef<T>) -> Option<NodeRef<T>>
+    where
+        T: Ord,
+    {
+        Node::iter(Rc::clone(root))
+            .reduce(|best, node| if node.borrow().value() < best.borrow().value() { node } else { best })
+    }
+
+    /// Find the node holding the largest value, ties going to the first one in breadth first order.
+    pub fn max_value(root: &NodeRef<T>) -> Option<NodeRef<T>>
+    where
+        T: Ord,
+    {
+        Node::iter(Rc::clone(root))
+            .reduce(|best, node| if node.borrow().value() > best.borrow().value() { node } else { best })
+    }
+
+    /// Count the distinct values held by the tree.
+    #[cfg(feature = "std")]
+    pub fn distinct_value_count(root: &NodeRef<T>) -> usize
+    where
+        T: Eq + Hash,
+    {
+        let nodes: Vec<_> = Node::iter(Rc::clone(root)).collect();
+        let borrowed: Vec<_> = nodes.iter().map(|node| node.borrow()).collect();
+        borrowed.iter().map(|n| n.value()).collect::<HashSet<_>>().len()
+    }
+
+    /// Count the distinct values held by the tree.
+    #[cfg(not(feature = "std"))]
+    pub fn distinct_value_count(root: &NodeRef<T>) -> usize
+    where
+        T: Ord,
+    {
+        let nodes: Vec<_> = Node::iter(Rc::clone(root)).collect();
+        let borrowed: Vec<_> = nodes.iter().map(|node| node.borrow()).collect();
+        borrowed.iter().map(|n| n.value()).collect::<BTreeSet<_>>().len()
+    }
+
+    /// Find the first value of `a`, in breadth first order, that `b` also holds.
+    #[cfg(feature = "std")]
+    pub fn first_shared_value(a: &NodeRef<T>, b: &NodeRef<T>) -> Option<T>
+    where
+        T: Eq + Hash + Clone,
+    {
+        let others: HashSet<T> = Node::iter_map(Rc::clone(b), T::clone).collect();
+        Node::iter_map(Rc::clone(a), T::clone).find(|value| others.contains(value))
+    }
+
+    /// Index the nodes of the tree by value, each value mapping to the first node
+    /// holding it in breadth first order. Later duplicates are ignored.
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, NodeRef, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent(1);
+    /// let child = Node::insert(&root, 2)?;
+    /// let index = Node::index_by_value(&root);
+    /// assert!(NodeRef::ptr_eq(&index[&2], &child));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn index_by_value(root: &NodeRef<T>) -> HashMap<T, NodeRef<T>>
+    where
+        T: Eq + Hash + Clone,
+    {
+        let mut index = HashMap::new();
+        for node in Node::iter(Rc::clone(root)) {
+            let value = node.borrow().value().clone();
+            index.entry(value).or_insert(node);
+        }
+        index
+    }
+
+    /// Hash the shape and values of the subtree under `node` with [`DefaultHasher`],
+    /// matching the [`Hash`] impl of [`Node`].
+    ///
+    /// ### Return
+    /// - A hash that is equal for any two subtrees with the same shape and values.
+    #[cfg(feature = "std")]
+    pub fn subtree_hash(node: &NodeRef<T>) -> u64
+    where
+        T: Hash,
+    {
+        let mut hasher = DefaultHasher::new();
+        node.borrow().hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Find the sequence of child indices leading from `root` to `target`.
+    ///
+    /// ### Return
+    /// - The index path to `target`, an empty path when `target` is `root`,
+    ///   or `None` if `target` is not part of the tree.
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent(1);
+    /// let _ = Node::insert(&root, 2)?;
+    /// let child = Node::insert(&root, 3)?;
+    /// let grand_child = Node::insert(&child, 4)?;
+    /// assert_eq!(Node::path_to(&root, &grand_child), Some(vec![1, 0]));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn path_to(root: &NodeRef<T>, target: &NodeRef<T>) -> Option<Vec<usize>> {
+        let mut stack = vec![(Rc::clone(root), Vec::new())];
+        while let Some((node, path)) = stack.pop() {
+            if Rc::ptr_eq(&node, target) {
+                return Some(path);
+            }
+            for (index, child) in node.borrow().children().iter().enumerate().rev() {
+                let mut child_path = path.clone();
+                child_path.push(index);
+                stack.push((Rc::clone(child), child_path));
+            }
+        }
+        None
+    }
+
+    /// Descend from `root` following a path of values, at each step moving into the first
+    /// child whose value matches. The root's own value is not part of the path,
+    /// an empty path returns `root`.
+    ///
+    /// ### Return
+    /// - The node at the end of the path, or `None` if any step has no matching child.
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, NodeRef, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent("usr");
+    /// let local = Node::insert(&root, "local")?;
+    /// let bin = Node::insert(&local, "bin")?;
+    ///
+    /// let found = Node::get_by_value_path(&root, &["local", "bin"]).unwrap();
+    /// assert!(NodeRef::ptr_eq(&found, &bin));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_by_value_path(root: &NodeRef<T>, path: &[T]) -> Option<NodeRef<T>>
+    where
+        T: PartialEq,
+    {
+        let mut current = Rc::clone(root);
+        for value in path {
+            let next = current
+                .borrow()
+                .children()
+                .iter()
+                .find(|child| child.borrow().value() == value)
+                .cloned()?;
+            current = next;
+        }
+        Some(current)
+    }
+
+    /// Visit every node depth first, letting `f` update its value given the node's depth
+    /// and its index among its siblings. The root is at depth `0` and index `0`.
+    ///
+    /// Each node is only borrowed while `f` runs on it, so no borrow is held while
+    /// its children are visited.
+    pub fn update_with_context<F>(root: &NodeRef<T>, mut f: F)
+    where
+        F: FnMut(&mut T, usize, usize),
+    {
+        let mut stack = vec![(Rc::clone(root), 0, 0)];
+        while let Some((node, depth, index)) = stack.pop() {
+            f(node.borrow_mut().value_mut(), depth, index);
+            let n = node.borrow();
+            stack.extend(
+                n.children()
+                    .iter()
+                    .enumerate()
+                    .rev()
+                    .map(|(index, child)| (Rc::clone(child), depth + 1, index)),
+            );
+        }
+    }
+
+    /// Apply `f` to the value of the node reached by following `path`, a list of child
+    /// indices starting at `root`. An empty path targets `root` itself.
+    ///
+    /// ### Return
+    /// - Result of an empty tuple, or [`NodeError::IndexOutOfBounds`] for the first index of `path`
+    ///   past the children of the node it is applied to.
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent(1);
+    /// let child = Node::insert(&root, 2)?;
+    /// Node::modify_at_path(&root, &[0], |value| *value *= 10)?;
+    /// assert_eq!(*child.borrow().value(), 20);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn modify_at_path<F>(root: &NodeRef<T>, path: &[usize], f: F) -> Result<(), NodeError>
+    where
+        F: FnOnce(&mut T),
+    {
+        let node = Node::node_at_path(root, path)?;
+        f(node.borrow_mut().value_mut());
+        Ok(())
+    }
+
+    /// Assign a clone of `new` to every node whose value matches `pred`.
+    ///
+    /// ### Return
+    /// - The number of values replaced.
+    pub fn replace_values<F>(root: &NodeRef<T>, pred: F, new: T) -> usize
+    where
+        T: Clone,
+        F: Fn(&T) -> bool,
+    {
+        let mut replaced = 0;
+        for node in Node::iter(Rc::clone(root)) {
+            let mut n = node.borrow_mut();
+            if pred(n.value()) {
+                *n.value_mut() = new.clone();
+                replaced += 1;
+            }
+        }
+        replaced
+    }
+
+    /// Update every value from its parent's, in breadth first order, by calling `f` with the
+    /// parent's already updated value and the child's value. The root is left unchanged.
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent(1);
+    /// let child = Node::insert(&root, 2)?;
+    /// let grand_child = Node::insert(&child, 3)?;
+    ///
+    /// Node::propagate(&root, |parent, child| *child += *parent);
+    /// assert_eq!(*grand_child.borrow().value(), 6);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn propagate<F>(root: &NodeRef<T>, f: F)
+    where
+        F: Fn(&T, &mut T),
+    {
+        for node in Node::iter(Rc::clone(root)) {
+            let n = node.borrow();
+            for child in n.children() {
+                f(n.value(), child.borrow_mut().value_mut());
+            }
+        }
+    }
+
+    /// Apply `f` to the value of every direct child of `parent`, in order.
+    ///
+    /// The borrow of `parent` is released before each child is borrowed mutably in turn.
+    ///
+    /// ### Return
+    /// - Result of an empty tuple, or [`NodeError::NotAParent`] if `parent` is a [`Node::Leaf`].
+    pub fn for_each_child_value_mut<F>(parent: &NodeRef<T>, mut f: F) -> Result<(), NodeError>
+    where
+        F: FnMut(&mut T),
+    {
+        let children = match &*parent.borrow() {
+            Self::Parent { next, .. } => next.clone(),
+            Self::Leaf { .. } => return Err(NodeError::NotAParent),
+        };
+        for child in &children {
+            f(child.borrow_mut().value_mut());
+        }
+        Ok(())
+    }
+
+    /// Follow `path`, a list of child indices, down from `root`.
+    fn node_at_path(root: &NodeRef<T>, path: &[usize]) -> Result<NodeRef<T>, NodeError> {
+        let mut node = Rc::clone(root);
+        for &index in path {
+            let child = {
+                let n = node.borrow();
+                let children = n.children();
+                children
+                    .get(index)
+                    .cloned()
+                    .ok_or(NodeError::IndexOutOfBounds { index, len: children.len() })?
+            };
+            node = child;
+        }
+        Ok(node)
+    }
+
+    /// Check if `path`, a list of child indices, leads from `root` down to a node, stopping
+    /// at the first index that is out of bounds. An empty `path` always resolves to `root`.
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent(1);
+    /// let _ = Node::insert(&root, 2)?;
+    /// assert!(Node::path_exists(&root, &[0]));
+    /// assert!(!Node::path_exists(&root, &[1]));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn path_exists(root: &NodeRef<T>, path: &[usize]) -> bool {
+        Node::node_at_path(root, path).is_ok()
+    }
+
+    /// Count the paths from `root` down to a node without children whose values add up to `target`.
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent(1);
+    /// let _ = Node::insert(&root, 2)?;
+    /// let _ = Node::insert(&root, 3)?;
+    /// assert_eq!(Node::count_paths_summing_to(&root, 3), 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn count_paths_summing_to(root: &NodeRef<T>, target: T) -> usize
+    where
+        T: Copy + core::ops::Add<Output = T> + PartialEq + Default,
+    {
+        let mut count = 0;
+        let mut stack = vec![(Rc::clone(root), T::default())];
+        while let Some((node, sum)) = stack.pop() {
+            let n = node.borrow();
+            let sum = sum + *n.value();
+            if n.has_children() {
+                stack.extend(n.children().iter().map(|child| (Rc::clone(child), sum)));
+            } else if sum == target {
+                count += 1;
+            }
+        }
+        count
+    }
+
+    /// Number of leading child indices shared by the paths from the root to `a` and to `b`.
+    ///
+    /// ### Return
+    /// - The length of the common prefix of both index paths, or `0` if they belong to different trees.
+    pub fn shared_prefix_len(a: &NodeRef<T>, b: &NodeRef<T>) -> usize {
+        let root = Node::root_of(a);
+        if !Rc::ptr_eq(&root, &Node::root_of(b)) {
+            return 0;
+        }
+        match (Node::path_to(&root, a), Node::path_to(&root, b)) {
+            (Some(a), Some(b)) => a.iter().zip(&b).take_while(|(a, b)| a == b).count(),
+            _ => 0,
+        }
+    }
+
+    /// Fetch the ancestor of `node` sitting at `depth`, where depth `0` is the root
+    /// and `node`'s own depth returns `node` itself.
+    ///
+    /// ### Return
+    /// - The ancestor at `depth`, or `None` if `depth` is deeper than `node`.
+    pub fn ancestor_at_depth(node: &NodeRef<T>, depth: usize) -> Option<NodeRef<T>> {
+        Node::lineage(node).get(depth).cloned()
+    }
+
+    /// Apply `f` to the value of every ancestor of `node`, from its parent up to the root.
+    /// `node` itself is left untouched.
+    pub fn for_each_ancestor_mut<F>(node: &NodeRef<T>, mut f: F)
+    where
+        F: FnMut(&mut T),
+    {
+        let mut current = node.borrow().parent_ref();
+        while let Some(ancestor) = current {
+            f(ancestor.borrow_mut().value_mut());
+            current = ancestor.borrow().parent_ref();
+        }
+    }
+
+    /// Clone the values on the path from the root down to `node`, both included.
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent(1);
+    /// let child = Node::insert(&root, 2)?;
+    /// assert_eq!(Node::path_values(&child), vec![1, 2]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn path_values(node: &NodeRef<T>) -> Vec<T>
+    where
+        T: Clone,
+    {
+        Node::lineage(node)
+            .iter()
+            .map(|node| node.borrow().value().clone())
+            .collect()
+    }
+
+    /// Walk up from the parent of `node`, returning the closest ancestor whose value matches `pred`.
+    /// `node` itself is never considered.
+    ///
+    /// ### Return
+    /// - The nearest matching ancestor, or `None` if no ancestor matches.
+    pub fn nearest_ancestor<F>(node: &NodeRef<T>, pred: F) -> Option<NodeRef<T>>
+    where
+        F: Fn(&T) -> bool,
+    {
+        let mut current = node.borrow().parent_ref();
+        while let Some(ancestor) = current {
+            if pred(ancestor.borrow().value()) {
+                return Some(ancestor);
+            }
+            current = ancestor.borrow().parent_ref();
+        }
+        None
+    }
+
+    /// Walk two trees together in breadth first order, calling `f` with each pair of values.
+    ///
+    /// ### Return
+    /// - Result of an empty tuple, or [`NodeError::ShapeMismatch`] as soon as a pair of nodes differ
+    ///   in variant or number of children, pairs visited before the mismatch were already passed to `f`.
+    pub fn zip_for_each<U, F>(a: &NodeRef<T>, b: &NodeRef<U>, mut f: F) -> Result<(), NodeError>
+    where
+        F: FnMut(&T, &U),
+    {
+        let mut queue = VecDeque::from([(Rc::clone(a), Rc::clone(b))]);
+        while let Some((a, b)) = queue.pop_front() {
+            let (a, b) = (a.borrow(), b.borrow());
+            if a.is_leaf() != b.is_leaf() || a.degree() != b.degree() {
+                return Err(NodeError::ShapeMismatch);
+            }
+            f(a.value(), b.value());
+            queue.extend(a.children().iter().cloned().zip(b.children().iter().cloned()));
+        }
+        Ok(())
+    }
+
+    /// Compare the shape of two trees, ignoring their values.
+    ///
+    /// Two trees are isomorphic when every pair of nodes visited in order are the same variant
+    /// and hold the same number of children.
+    pub fn is_isomorphic<U>(a: &NodeRef<T>, b: &NodeRef<U>) -> bool {
+        let mut stack = vec![(Rc::clone(a), Rc::clone(b))];
+        while let Some((a, b)) = stack.pop() {
+            let (a, b) = (a.borrow(), b.borrow());
+            if a.is_leaf() != b.is_leaf() || a.children().len() != b.children().len() {
+                return false;
+            }
+            stack.extend(
+                a.children()
+                    .iter()
+                    .cloned()
+                    .zip(b.children().iter().cloned()),
+            );
+        }
+        true
+    }
+
+    /// Check whether `a` and `b` hold equal values in the same shape, the structural equality
+    /// of the two subtrees. Unlike [`Node::is_isomorphic`] every pair of values must also be equal.
+    pub fn value_eq(a: &NodeRef<T>, b: &NodeRef<T>) -> bool
+    where
+        T: PartialEq,
+    {
+        let mut stack = vec![(Rc::clone(a), Rc::clone(b))];
+        while let Some((a, b)) = stack.pop() {
+            let (a, b) = (a.borrow(), b.borrow());
+            if a.value() != b.value() || a.children().len() != b.children().len() {
+                return false;
+            }
+            stack.extend(
+                a.children()
+                    .iter()
+                    .cloned()
+                    .zip(b.children().iter().cloned()),
+            );
+        }
+        true
+    }
+
+    /// Check whether some node of `haystack` roots a subtree equal to `needle`, see [`Node::value_eq`].
+    ///
+    /// ### Example
+    /// ```
+    /// # use libcanopy::{Node, error::NodeError};
+    /// # fn main() -> Result<(), NodeError> {
+    /// let root = Node::parent(1);
+    /// let child = Node::insert(&root, 2)?;
+    /// let _ = Node::insert(&child, 3)?;
+    ///
+    /// let needle = Node::parent(2);
+    /// let _ = Node::insert(&needle, 3)?;
+    /// assert!(Node::is_subtree_of(&needle, &root));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn is_subtree_of(needle: &NodeRef<T>, haystack: &NodeRef<T>) -> bool
+    where
+        T: PartialEq,
+    {
+        Node::iter(Rc::clone(haystack)).any(|candidate| Node::value_eq(needle, &candidate))
+    }
+}
+
+/// Breadth first iterator over a tree of [`NodeRef<T>`].
+///
+/// The queue is a [`VecDeque`], from `alloc` under `no_std`, so taking the next node is `O(1)`.
+pub struct NodeIter<T> {
+    queue: VecDeque<NodeRef<T>>,
+}
+
+impl<T> NodeIter<T> {
+    pub fn new(node: NodeRef<T>) -> NodeIter<T> {
+        let queue = VecDeque::from([node]);
+        NodeIter { queue }
+    }
+
+    /// Start over from `root`, dropping whatever was left to visit while keeping the
+    /// queue's allocation.
+    #[inline]
+    pub fn reset(&mut self, root: NodeRef<T>) {
+        self.queue.clear();
+        self.queue.push_back(root);
+    }
+
+    /// The node the next call to [`Iterator::next`] returns, without consuming it.
+    #[inline]
+    pub fn peek(&self) -> Option<&NodeRef<T>> {
+        self.queue.front()
+    }
+}
+impl<T> Iterator for NodeIter<T> {
+    type Item = NodeRef<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.queue.pop_front()?; // Remove first element (FIFO)
+
+        if let Node::Parent { next, .. } = &*item.borrow() {
+            self.queue.extend(next.iter().cloned()); // Add children to queue
+        }
+
+        Some(item)
+    }
+}
+
+/// Owned copy of a tree's values and shape, taken with [`Node::snapshot`].
+///
+/// Nodes are stored in pre-order alongside their number of children, and whether they are
+/// a [`Node::Parent`]. The entries are private, so a snapshot always describes a whole tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeSnapshot<T> {
+    nodes: Vec<(T, usize, bool)>,
+}
+
+impl<T> TreeSnapshot<T> {
+    /// Number of nodes held by the snapshot.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// A snapshot always holds at least the root.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+}
+
+/// Subtree sizes of every node in a tree, computed once and looked up by node identity.
+///
+/// The cache is a copy taken at construction, any later mutation of the tree invalidates it
+/// and it must be rebuilt with [`SizeCache::new`]. Nodes are keyed by address, and the cache
+/// holds a refrence to each of them so that no address is reused by another node while the
+/// cache exists. A lookup for a node created after the cache was built therefore yields `None`,
+/// but nodes removed from the tree are only freed once the cache is dropped.
+///
+/// ### Example
+/// ```
+/// # use libcanopy::{Node, SizeCache, error::NodeError};
+/// # fn main() -> Result<(), NodeError> {
+/// let root = Node::parent(1);
+/// let child = Node::insert(&root, 2)?;
+///
+/// let cache = SizeCache::new(&root);
+/// assert_eq!(cache.size_of(&root), Some(2));
+/// assert_eq!(cache.size_of(&child), Some(1));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SizeCache<T> {
+    sizes: BTreeMap<*const RefCell<Node<T>>, (NodeRef<T>, usize)>,
+}
+
+impl<T> SizeCache<T> {
+    /// Walk the tree under `root` once, recording the size of every subtree.
+    pub fn new(root: &NodeRef<T>) -> SizeCache<T> {
+        let mut sizes: BTreeMap<_, (NodeRef<T>, usize)> = BTreeMap::new();
+        // post-order guarantees every child is sized before its parent
+        for node in Node::iter_post_order(Rc::clone(root)) {
+            let size = 1 + node
+                .borrow()
+                .children()
+                .iter()
+                .map(|child| sizes[&Rc::as_ptr(child)].1)
+                .sum::<usize>();
+            sizes.insert(Rc::as_ptr(&node), (Rc::clone(&node), size));
+        }
+        SizeCache { sizes }
+    }
+
+    /// Number of nodes in the subtree rooted at `node`, itself included.
+    ///
+    /// ### Return
+    /// - `None` if `node` was not part of the tree when the cache was built.
+    #[inline]
+    pub fn size_of(&self, node: &NodeRef<T>) -> Option<usize> {
+        self.sizes.get(&Rc::as_ptr(node)).map(|(_, size)| *size)
+    }
+
+    /// Number of nodes held by the cache.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.sizes.len()
+    }
+
+    /// A cache always holds at least the root.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.sizes.is_empty()
+    }
+}
+
+/// Order in which a tree is walked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraversalOrder {
+    /// Level by level, left to right.
+    Bfs,
+    /// Depth first, each node before its children.
+    PreOrder,
+    /// Depth first, each node after its children.
+    PostOrder,
+}
+
+/// Depth first (pre-order) iterator over a tree of [`NodeRef<T>`].
+pub struct DfsIter<T> {
+    stack: Vec<NodeRef<T>>,
+}
+
+impl<T> DfsIter<T> {
+    pub fn new(node: NodeRef<T>) -> DfsIter<T> {
+        let stack = vec![node];
+        DfsIter { stack }
+    }
+}
+
+impl<T> Iterator for DfsIter<T> {
+    type Item = NodeRef<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.stack.pop()?; // Remove last element (LIFO)
+
+        if let Node::Parent { next, .. } = &*item.borrow() {
+            // push in reverse so the first child is visited first
+            self.stack.extend(next.iter().rev().cloned());
+        }
+
+        Some(item)
+    }
+}
+
+/// Depth first (post-order) iterator over a tree of [`NodeRef<T>`].
+pub struct PostOrderIter<T> {
+    // each entry records whether its children were already pushed
+    stack: Vec<(NodeRef<T>, bool)>,
+}
+
+impl<T> PostOrderIter<T> {
+    pub fn new(node: NodeRef<T>) -> PostOrderIter<T> {
+        let stack = vec![(node, false)];
+        PostOrderIter { stack }
+    }
+}
+
+impl<T> Iterator for PostOrderIter<T> {
+    type Item = NodeRef<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (item, expanded) = self.stack.pop()?;
+            if expanded || !item.borrow().has_children() {
+                return Some(item);
+            }
+
+            let children: Vec<_> = item.borrow().children().iter().rev().cloned().collect();
+            self.stack.push((item, true));
+            self.stack.extend(children.into_iter().map(|child| (child, false)));
+        }
+    }
+}
+
+/// Breadth first iterator yielding each node alongside its position in its parent's children.
+///
+/// The root is always yielded with index `0`.
+pub struct SiblingIndexIter<T> {
+    queue: VecDeque<(usize, NodeRef<T>)>,
+}
+
+impl<T> SiblingIndexIter<T> {
+    pub fn new(node: NodeRef<T>) -> SiblingIndexIter<T> {
+        let queue = VecDeque::from([(0, node)]);
+        SiblingIndexIter { queue }
+    }
+}
+
+impl<T> Iterator for SiblingIndexIter<T> {
+    type Item = (usize, NodeRef<T>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (index, item) = self.queue.pop_front()?;
+
+        if let Node::Parent { next, .. } = &*item.borrow() {
+            self.queue.extend(next.iter().cloned().enumerate());
+        }
+
+        Some((index, item))
+    }
+}
+
+/// Breadth first iterator over every `(parent, child)` link of a tree.
+pub struct EdgeIter<T> {
+    queue: VecDeque<(NodeRef<T>, NodeRef<T>)>,
+}
+
+impl<T> EdgeIter<T> {
+    pub fn new(node: NodeRef<T>) -> EdgeIter<T> {
+        let mut iter = EdgeIter {
+            queue: VecDeque::new(),
+        };
+        iter.enqueue_children(&node);
+        iter
+    }
+
+    fn enqueue_children(&mut self, parent: &NodeRef<T>) {
+        self.queue.extend(
+            parent
+                .borrow()
+                .children()
+                .iter()
+                .map(|child| (Rc::clone(parent), Rc::clone(child))),
+        );
+    }
+}
+
+impl<T> Iterator for EdgeIter<T> {
+    type Item = (NodeRef<T>, NodeRef<T>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (parent, child) = self.queue.pop_front()?;
+        self.enqueue_children(&child);
+        Some((parent, child))
     }
 }
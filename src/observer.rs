@@ -0,0 +1,158 @@
+//! Hooks for being notified of structural changes within a tree.
+//!
+//! An observer is registered on a root with [`Node::set_observer`](crate::Node::set_observer),
+//! which requires the `std` feature, and hears about every child linked into, or unlinked from,
+//! a node of that tree, whichever operation made the change. Moving a node, as done by
+//! [`Node::splice_out`](crate::Node::splice_out) or [`Node::collapse_chains`](crate::Node::collapse_chains),
+//! is reported as its removal followed by its insertion under the new parent.
+//!
+//! [`Node::reorder_child`](crate::Node::reorder_child) and [`Node::set_parent`](crate::Node::set_parent)
+//! leave every child list as it was and are not reported, nor is [`Node::reroot`](crate::Node::reroot),
+//! which hands the observer over to the new root instead.
+//!
+//! Observers are kept out of the nodes, in a table of the current thread keyed by root, so a
+//! tree without an observer pays nothing for the hook. A root that becomes the child of another
+//! node loses its observer, and cloning a node never carries one over. The observer of a dropped
+//! root is released the next time an observer is registered.
+use crate::node::NodeRef;
+
+#[cfg(not(feature = "std"))]
+use alloc::rc::Rc;
+
+#[cfg(feature = "std")]
+use crate::node::Node;
+#[cfg(feature = "std")]
+use std::{
+    cell::RefCell,
+    collections::BTreeMap,
+    rc::{Rc, Weak},
+};
+
+/// Receives notifications when nodes are linked into, or removed from, an observed tree.
+///
+/// Both callbacks run after the change was made and while no node is borrowed,
+/// so the tree is free to be inspected from within them.
+pub trait TreeObserver<T> {
+    /// Called after `child` was inserted beneath `parent`.
+    fn on_insert(&self, _parent: &NodeRef<T>, _child: &NodeRef<T>) {}
+
+    /// Called after `child` was removed from `parent`.
+    fn on_remove(&self, _parent: &NodeRef<T>, _child: &NodeRef<T>) {}
+}
+
+/// A shared handle to a [`TreeObserver`].
+pub type ObserverRef<T> = Rc<dyn TreeObserver<T>>;
+
+/// An observer along with a weak refrence to the root it is registered on. The weak refrence
+/// keeps the root's allocation, and so the address the entry is keyed by, from being reused
+/// by another node while the entry exists.
+#[cfg(feature = "std")]
+struct Registration<T> {
+    root: Weak<RefCell<Node<T>>>,
+    observer: ObserverRef<T>,
+}
+
+#[cfg(feature = "std")]
+trait Registered {
+    /// Check if the root of the entry is still alive.
+    fn is_alive(&self) -> bool;
+}
+
+#[cfg(feature = "std")]
+impl<T> Registered for RefCell<Registration<T>> {
+    fn is_alive(&self) -> bool {
+        self.borrow().root.strong_count() > 0
+    }
+}
+
+#[cfg(feature = "std")]
+type Registry = BTreeMap<usize, Rc<dyn Registered>>;
+
+#[cfg(feature = "std")]
+thread_local! {
+    /// Observed roots of the current thread, keyed by the address of the root.
+    static REGISTRY: RefCell<Registry> = const { RefCell::new(BTreeMap::new()) };
+}
+
+#[cfg(feature = "std")]
+#[inline]
+fn key<T>(root: &NodeRef<T>) -> usize {
+    Rc::as_ptr(root) as usize
+}
+
+#[cfg(feature = "std")]
+fn downcast<T>(entry: &Rc<dyn Registered>) -> &RefCell<Registration<T>> {
+    // SAFETY: an entry is only ever stored under the address of the root it was created for,
+    // and its weak refrence keeps that allocation from being freed and handed to another node.
+    // Any `NodeRef<T>` found at that address is therefore the registered root, so the entry
+    // was created as a `RefCell<Registration<T>>` with this very `T`.
+    unsafe { &*(Rc::as_ptr(entry) as *const RefCell<Registration<T>>) }
+}
+
+/// Check if any root of the current thread holds an observer. This is all a structural change
+/// costs while no observer is registered, so entries are never pruned from here.
+#[cfg(feature = "std")]
+#[inline]
+pub(crate) fn is_observing() -> bool {
+    REGISTRY.with(|registry| !registry.borrow().is_empty())
+}
+
+/// Removes the entries of roots that no longer exist, returning them so they are dropped once
+/// the registry is no longer borrowed, as dropping an observer may run arbitrary code.
+#[cfg(feature = "std")]
+fn prune(registry: &mut Registry) -> Vec<Rc<dyn Registered>> {
+    let dead: Vec<usize> = registry
+        .iter()
+        .filter(|(_, entry)| !entry.is_alive())
+        .map(|(key, _)| *key)
+        .collect();
+    dead.iter().filter_map(|key| registry.remove(key)).collect()
+}
+
+/// Registers `observer` on `root`, returning the observer it replaces. The entries of roots
+/// dropped since the last registration are released along the way.
+#[cfg(feature = "std")]
+pub(crate) fn register<T: 'static>(root: &NodeRef<T>, observer: ObserverRef<T>) -> Option<ObserverRef<T>> {
+    let entry: Rc<dyn Registered> = Rc::new(RefCell::new(Registration {
+        root: Rc::downgrade(root),
+        observer,
+    }));
+    let (previous, dead) = REGISTRY.with(|registry| {
+        let mut registry = registry.borrow_mut();
+        let dead = prune(&mut registry);
+        (registry.insert(key(root), entry), dead)
+    });
+    drop(dead);
+    let observer = Rc::clone(&downcast::<T>(&previous?).borrow().observer);
+    Some(observer)
+}
+
+/// Removes the observer registered on `root`, returning it.
+#[cfg(feature = "std")]
+pub(crate) fn unregister<T>(root: &NodeRef<T>) -> Option<ObserverRef<T>> {
+    let entry = REGISTRY.with(|registry| registry.borrow_mut().remove(&key(root)))?;
+    let observer = Rc::clone(&downcast::<T>(&entry).borrow().observer);
+    Some(observer)
+}
+
+/// The observer registered on `root`, if there is one.
+#[cfg(feature = "std")]
+pub(crate) fn observer_of<T>(root: &NodeRef<T>) -> Option<ObserverRef<T>> {
+    REGISTRY.with(|registry| {
+        let registry = registry.borrow();
+        let entry = registry.get(&key(root))?;
+        Some(Rc::clone(&downcast::<T>(entry).borrow().observer))
+    })
+}
+
+/// Moves the observer registered on `from` over to `to`, if there is one.
+#[cfg(feature = "std")]
+pub(crate) fn transfer<T>(from: &NodeRef<T>, to: &NodeRef<T>) {
+    let replaced = REGISTRY.with(|registry| {
+        let mut registry = registry.borrow_mut();
+        let entry = registry.remove(&key(from))?;
+        downcast::<T>(&entry).borrow_mut().root = Rc::downgrade(to);
+        registry.insert(key(to), entry)
+    });
+    drop(replaced);
+}
@@ -0,0 +1,113 @@
+//! An optionally empty container around a tree of [`Node<T>`].
+//!
+//! Every [`NodeRef<T>`] is a node, so a tree built from them always holds at least its root.
+//! [`Tree`] models the empty case, and operations that need a root return
+//! [`NodeError::EmptyTree`] when there is none.
+use crate::{
+    error::NodeError,
+    node::{Node, NodeRef},
+};
+
+use core::fmt::Debug;
+
+#[cfg(not(feature = "std"))]
+use rclite::Rc;
+
+#[cfg(feature = "std")]
+use std::rc::Rc;
+
+/// A tree that may be empty, holding its root when it has one.
+///
+/// ### Example
+/// ```
+/// # use libcanopy::{Tree, error::NodeError};
+/// # fn main() -> Result<(), NodeError> {
+/// let empty: Tree<u8> = Tree::new();
+/// assert_eq!(empty.root().err(), Some(NodeError::EmptyTree));
+///
+/// let tree = Tree::with_root(1);
+/// let _ = tree.insert(2)?;
+/// assert_eq!(tree.len(), 2);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Tree<T> {
+    root: Option<NodeRef<T>>,
+}
+
+impl<T> Tree<T> {
+    /// Create an empty tree.
+    #[inline]
+    pub fn new() -> Tree<T> {
+        Tree { root: None }
+    }
+
+    /// Create a tree holding a single [`Node::Parent`] root with `value`.
+    #[inline]
+    pub fn with_root(value: T) -> Tree<T>
+    where
+        T: Debug,
+    {
+        Tree {
+            root: Some(Node::parent(value)),
+        }
+    }
+
+    /// ### Return
+    /// - Result of the root, or [`NodeError::EmptyTree`] if the tree is empty.
+    #[inline]
+    pub fn root(&self) -> Result<&NodeRef<T>, NodeError> {
+        self.root.as_ref().ok_or(NodeError::EmptyTree)
+    }
+
+    /// Check if the tree holds no root.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /// Number of nodes in the tree, `0` when it is empty.
+    pub fn len(&self) -> usize {
+        self.root
+            .as_ref()
+            .map_or(0, |root| Node::iter(Rc::clone(root)).count())
+    }
+
+    /// Insert a new child with `value` under the root, see [`Node::insert`].
+    ///
+    /// ### Return
+    /// - Result of the new child, or [`NodeError::EmptyTree`] if the tree is empty.
+    pub fn insert(&self, value: T) -> Result<NodeRef<T>, NodeError>
+    where
+        T: Debug + Default + Clone,
+    {
+        Node::insert(self.root()?, value)
+    }
+}
+
+impl<T> Default for Tree<T> {
+    fn default() -> Self {
+        Tree::new()
+    }
+}
+
+/// Cloning a tree hands out another refrence to the same root, the nodes themselves
+/// are shared and not copied, use [`Node::deep_clone`] for that.
+impl<T> Clone for Tree<T> {
+    fn clone(&self) -> Self {
+        Tree {
+            root: self.root.clone(),
+        }
+    }
+}
+
+/// Wraps `root` in a tree, failing with [`NodeError::ExpectedARootNode`] if it is not a root.
+impl<T> TryFrom<NodeRef<T>> for Tree<T> {
+    type Error = NodeError;
+
+    fn try_from(root: NodeRef<T>) -> Result<Self, Self::Error> {
+        root.try_borrow()?.expect_root()?;
+        Ok(Tree { root: Some(root) })
+    }
+}